@@ -1,3 +1,4 @@
 pub mod builder;
 mod debug_printer;
 mod printer;
+pub mod wiki_link;
@@ -42,3 +42,17 @@ pub fn paragraph(children: Vec<Node>) -> Node {
         position: None,
     })
 }
+
+pub fn strong(children: Vec<Node>) -> Node {
+    Node::Strong(Strong {
+        children,
+        position: None,
+    })
+}
+
+pub fn inline_code(value: &str) -> Node {
+    Node::InlineCode(InlineCode {
+        value: value.to_string(),
+        position: None,
+    })
+}
@@ -6,6 +6,7 @@ use markdown::mdast::{
     Node, Table, TableCell, TableRow, Text,
 };
 
+use super::wiki_link::wiki_link_of;
 use crate::printer::Printer;
 
 const INDENT: &str = "    ";
@@ -25,6 +26,14 @@ impl Printer for Node {
     }
 }
 
+impl Printer for [Node] {
+    type Options = ();
+
+    fn print(&self, _options: Self::Options) -> Result<String> {
+        AstPrinter::print_children(self)
+    }
+}
+
 impl AstPrinter {
     pub fn print(node: &Node) -> Result<String> {
         let mut printer = AstPrinter {
@@ -34,6 +43,15 @@ impl AstPrinter {
         Ok(printer.print_root(node)?.trim().to_string())
     }
 
+    /// Prints inline nodes as they are, without inserting separators between them.
+    pub fn print_children(children: &[Node]) -> Result<String> {
+        let mut printer = AstPrinter {
+            depth: 0,
+            order: None,
+        };
+        Ok(printer.map_children(children, None)?.trim().to_string())
+    }
+
     fn print_root(&mut self, node: &Node) -> Result<String> {
         match node {
             // Parent
@@ -85,19 +103,38 @@ impl AstPrinter {
             Node::Paragraph(node) => {
                 let mut ends_with_separators = false;
                 let mut s = String::new();
+                let links = node
+                    .children
+                    .iter()
+                    .map(|v| wiki_link_of(v).is_some())
+                    .collect::<Vec<bool>>();
                 for (i, child) in node.children.iter().enumerate() {
-                    let v = self
-                        .print_root(child)
-                        .map(|v| v.trim_matches(' ').to_string())?;
+                    let raw = self.print_root(child)?;
+                    // A wiki link stays as written next to other inline content
+                    let after_link = i > 0 && links[i - 1];
+                    let before_link = links.get(i + 1) == Some(&true);
+                    let v = if after_link {
+                        &raw
+                    } else {
+                        raw.trim_start_matches(' ')
+                    };
+                    let v = if before_link {
+                        v
+                    } else {
+                        v.trim_end_matches(' ')
+                    };
                     if i > 0
                         && !v.is_empty()
                         && !ends_with_separators
-                        && !starts_with_trailing_separators(&v)
+                        && !after_link
+                        && !links[i]
+                        && !v.starts_with(' ')
+                        && !starts_with_trailing_separators(v)
                     {
                         s.push(' ');
                     }
-                    s.push_str(&v);
-                    ends_with_separators = v.ends_with(&['\n', ' ']);
+                    s.push_str(v);
+                    ends_with_separators = v.ends_with(['\n', ' ']);
                 }
                 Ok(format!("{}\n", s.trim()))
             },
@@ -174,6 +211,7 @@ impl AstPrinter {
             Node::Math(Math { value, .. }) => Ok(format!("$$\n{value}\n$$")),
             Node::InlineMath(InlineMath { value, .. }) => Ok(format!("${value}$")),
             Node::Yaml(node) => Ok(format!("---\n{}---\n", node.value)),
+            Node::Toml(node) => Ok(format!("+++\n{}\n+++\n", node.value)),
             Node::FootnoteReference(FootnoteReference { identifier, .. }) => {
                 Ok(format!("[^{identifier}]"))
             },
@@ -190,7 +228,7 @@ impl AstPrinter {
             .iter()
             .map(|node| self.print_root(node))
             .collect::<Result<Vec<String>>>()?
-            .join(if let Some(sep) = sep { sep } else { "" }))
+            .join(sep.unwrap_or_default()))
     }
 }
 
@@ -0,0 +1,170 @@
+use markdown::{
+    mdast::{Node, Text},
+    unist::{Point, Position},
+};
+
+use crate::note::{WikiLink, WIKI_LINK};
+
+/// Makes each wiki link in inline content a text node of its own, written as in `source`, the
+/// text `node` was parsed from. Markdown syntax in a link, e.g. `[[a*b*c]]`, is parsed as
+/// such, so the nodes it spans are replaced.
+pub fn split(node: &mut Node, source: &str) {
+    let Some(children) = node.children_mut() else {
+        return;
+    };
+    split_children(children, source);
+    for child in children {
+        split(child, source);
+    }
+}
+
+/// The wiki link `node` is, after `split`.
+pub fn wiki_link_of(node: &Node) -> Option<WikiLink> {
+    match node {
+        Node::Text(v) if v.value.starts_with(['[', '!']) => v.value.parse().ok(),
+        _ => None,
+    }
+}
+
+fn split_children(children: &mut Vec<Node>, source: &str) {
+    let mut i = 0;
+    while i < children.len() {
+        let Some((start, end)) = text_range(&children[i]) else {
+            i += 1;
+            continue;
+        };
+        let mut found = None;
+        let mut at = start;
+        while let Some(m) = WIKI_LINK.find_at(source, at).filter(|v| v.start() < end) {
+            at = m.start() + 1;
+            // `\[[foo]]` isn't a link
+            if source[..m.start()].ends_with('\\') {
+                continue;
+            }
+            // The link has to end in text of the same parent, unlike `[[1]](url)`
+            let last = children[i..]
+                .iter()
+                .position(|v| text_range(v).is_some_and(|(s, e)| s < m.end() && m.end() <= e));
+            if let Some(last) = last {
+                found = Some((m.range(), i + last));
+                break;
+            }
+        }
+        let Some((link, last)) = found else {
+            i += 1;
+            continue;
+        };
+
+        let last_end = text_range(&children[last]).map_or(link.end, |(_, e)| e);
+        let mut nodes = vec![];
+        if start < link.start {
+            nodes.push(text(
+                slice(&children[i], source, start..link.start, true),
+                source,
+                start..link.start,
+            ));
+        }
+        nodes.push(text(source[link.clone()].to_string(), source, link.clone()));
+        let has_rest = link.end < last_end;
+        if has_rest {
+            nodes.push(text(
+                slice(&children[last], source, link.end..last_end, false),
+                source,
+                link.end..last_end,
+            ));
+        }
+        let count = nodes.len();
+        children.splice(i..=last, nodes);
+        // The rest may have more links
+        i += if has_rest { count - 1 } else { count };
+    }
+}
+
+fn text_range(node: &Node) -> Option<(usize, usize)> {
+    match node {
+        Node::Text(v) => v.position.as_ref().map(|v| (v.start.offset, v.end.offset)),
+        _ => None,
+    }
+}
+
+/// Part of a text node's value. Its source is used if the value differs from it, e.g. when it
+/// has escapes.
+fn slice(node: &Node, source: &str, range: std::ops::Range<usize>, head: bool) -> String {
+    if let (Node::Text(v), Some((start, end))) = (node, text_range(node)) {
+        if source[start..end] == v.value {
+            return if head {
+                v.value[..range.end - start].to_string()
+            } else {
+                v.value[range.start - start..].to_string()
+            };
+        }
+    }
+    source[range].to_string()
+}
+
+fn text(value: String, source: &str, range: std::ops::Range<usize>) -> Node {
+    Node::Text(Text {
+        value,
+        position: Some(Position {
+            start: point(source, range.start),
+            end: point(source, range.end),
+        }),
+    })
+}
+
+fn point(source: &str, offset: usize) -> Point {
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before[before.rfind('\n').map_or(0, |v| v + 1)..]
+        .chars()
+        .count()
+        + 1;
+    Point::new(line, column, offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::to_mdast_from_str;
+
+    fn texts(s: &str) -> Vec<String> {
+        let node = to_mdast_from_str(s).unwrap();
+        let paragraph = &node.children().unwrap()[0];
+        paragraph
+            .children()
+            .unwrap()
+            .iter()
+            .map(|v| match v {
+                Node::Text(v) => v.value.clone(),
+                v => format!("{v:?}")
+                    .split([' ', '('])
+                    .next()
+                    .unwrap()
+                    .to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn split_links() {
+        assert_eq!(
+            texts("foo [[a*b*c|d]] and [[e]]."),
+            vec!["foo ", "[[a*b*c|d]]", " and ", "[[e]]", "."]
+        );
+        assert_eq!(texts("**a**[[b]]"), vec!["Strong", "[[b]]"]);
+        assert_eq!(texts("[[1]](url) and \\[[c]]"), vec!["Link", " and [[c]]"]);
+    }
+
+    #[test]
+    fn positions() {
+        let node = to_mdast_from_str("foo\nbar [[baz]]").unwrap();
+        let link = &node.children().unwrap()[0].children().unwrap()[1];
+        assert_eq!(wiki_link_of(link).map(|v| v.target), Some("baz".into()));
+        assert_eq!(
+            link.position().map(|v| (v.start.line, v.start.column)),
+            Some((2, 5))
+        );
+    }
+}
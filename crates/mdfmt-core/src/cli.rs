@@ -1,4 +1,5 @@
 mod config;
+mod config_file;
 mod quoted_args;
 
-pub use config::Config;
+pub use config::{Command, Config};
@@ -1,14 +1,24 @@
 use std::{io, path::PathBuf};
 
 use anyhow::{Context, Result};
-use clap::Parser as ClaspParser;
+use chrono::FixedOffset;
+use clap::{Parser as ClaspParser, Subcommand};
 
-use super::quoted_args::parse_quoted_args;
+use super::{config_file::ConfigFile, quoted_args::parse_quoted_args};
+use crate::index::{GraphFormat, IndexFormat};
+use crate::lint::{LintFormat, LintOptions};
+use crate::note::{
+    CreatedSource, FormatOptions, FrontMatterFormat, FrontMatterOptions, HeadingLevel,
+    IndexOptions, JournalOptions, SlugStyle, TimestampOptions, TocLinkStyle, TocOptions, TocStyle,
+};
 
 /// Simple Markdown Formatter
 #[derive(ClaspParser, Debug)]
 #[command(version)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Files
     files: Option<Vec<PathBuf>>,
 
@@ -22,6 +32,30 @@ struct Args {
     #[arg(long)]
     index: Option<String>,
 
+    /// Index format: `json`, `ndjson`, `csv` or `sqlite`
+    #[arg(long, default_value = "json")]
+    index_format: IndexFormat,
+
+    /// Index only notes with front matter
+    #[arg(long)]
+    index_metadata_only: bool,
+
+    /// Add the outline, word counts, tasks, cards and links of each note to the index
+    #[arg(long)]
+    index_details: bool,
+
+    /// Parse every note instead of reusing `<index>.cache` for unchanged ones
+    #[arg(long)]
+    no_index_cache: bool,
+
+    /// Write the links between notes, their backlinks and unresolved links to a file
+    #[arg(long)]
+    graph: Option<String>,
+
+    /// Graph format: `json` or `dot`
+    #[arg(long, default_value = "json")]
+    graph_format: GraphFormat,
+
     /// Overwrite
     #[arg(short, long, default_value = "false")]
     write: bool,
@@ -38,20 +72,154 @@ struct Args {
     #[arg(long)]
     check: bool,
 
+    /// Report style problems such as skipped heading levels, and fail on errors. Rules are set in
+    /// `[lint.rules]`
+    #[arg(long)]
+    lint: bool,
+
+    /// Lint output: `text` or `json`
+    #[arg(long, default_value = "text")]
+    lint_format: LintFormat,
+
+    /// List `status` and `kind` values that aren't recognized, with the notes using them
+    #[arg(long)]
+    unknown_values: bool,
+
+    /// Check front matter against `--schema` and exit with an error on violations
+    #[arg(long, requires = "schema")]
+    validate: bool,
+
+    /// Front matter schema, in JSON Schema or TOML
+    #[arg(long)]
+    schema: Option<PathBuf>,
+
+    /// Heading levels: `normalize` (strict hierarchy) or `keep` (as written)
+    #[arg(long, default_value = "normalize")]
+    heading_level: HeadingLevel,
+
+    /// Heading anchors: `github`, `obsidian` or `custom[:<separator>]`
+    #[arg(long, default_value = "github")]
+    slug_style: SlugStyle,
+
+    /// Regenerate every `> [!toc]` block from the note's headings
+    #[arg(long)]
+    toc_auto: bool,
+
+    /// Shallowest heading level in a generated TOC
+    #[arg(long, default_value = "1")]
+    toc_min_depth: u8,
+
+    /// Deepest heading level in a generated TOC
+    #[arg(long, default_value = "6")]
+    toc_max_depth: u8,
+
+    /// Links in a generated TOC: `none`, `obsidian` (`[[#Heading]]`) or `markdown`
+    #[arg(long, default_value = "none")]
+    toc_link: TocLinkStyle,
+
+    /// Generated TOC entries: `bullet`, `ordered` or `hierarchical` (`- 1.2 Heading`)
+    #[arg(long, default_value = "bullet")]
+    toc_style: TocStyle,
+
+    /// Prefix headings with hierarchical numbers such as `1.2.`
+    #[arg(long)]
+    number_headings: bool,
+
+    /// Front matter keys to print first, e.g. `title,aliases,tags`
+    #[arg(long, value_delimiter = ',')]
+    key_order: Vec<String>,
+
+    /// Keep the remaining front matter keys in their original order
+    #[arg(long)]
+    keep_key_order: bool,
+
+    /// Convert front matter to `yaml`, `toml` or `json`
+    #[arg(long)]
+    front_matter_format: Option<FrontMatterFormat>,
+
+    /// Fill a missing `createdAt`, and set `updatedAt` when `--write` changes the body
+    #[arg(long)]
+    timestamps: bool,
+
+    /// Source of a missing `createdAt`: `git` (falls back to `ctime`) or `ctime`, the file's
+    /// creation time, or its modification time where the file system doesn't record one
+    #[arg(long)]
+    created_from: Option<CreatedSource>,
+
+    /// `strftime` format of `createdAt` and `updatedAt`, e.g. `%Y-%m-%d %H:%M`
+    #[arg(long)]
+    timestamp_format: Option<String>,
+
+    /// `strftime` format of `journalDate`, e.g. `%Y/%m/%d`
+    #[arg(long)]
+    date_format: Option<String>,
+
+    /// Extra `strftime` format accepted in date fields, e.g. `%d.%m.%Y` (repeatable)
+    #[arg(long)]
+    date_input_format: Vec<String>,
+
+    /// Offset to print `createdAt` and `updatedAt` in, e.g. `+09:00`
+    #[arg(long, allow_hyphen_values = true)]
+    timezone: Option<FixedOffset>,
+
+    /// Offset of dates and times written without one, e.g. `+09:00`
+    #[arg(long, allow_hyphen_values = true)]
+    default_timezone: Option<FixedOffset>,
+
+    /// Path pattern of daily notes, e.g. `journal/{year}/{month}/{day}.md` (repeatable)
+    #[arg(long)]
+    journal_pattern: Vec<String>,
+
+    /// Write the date from `--journal-pattern` into `journalDate`
+    #[arg(long)]
+    write_journal_date: bool,
+
+    /// Settings file in TOML, e.g. the `[status]` workflow
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
     /// Read files from stdin
     #[arg(long, default_value = "false")]
     stdin: bool,
 }
 
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Change a note's status and update `updatedAt`
+    Status { file: PathBuf, status: String },
+    /// Check links in `--glob` files, or `**/*.md`, for missing notes, headings and footnotes
+    Links,
+    /// Move a note and update the links to it in `--glob` files, or `**/*.md`
+    Mv {
+        old: PathBuf,
+        new: PathBuf,
+        /// Print the edits without changing any file
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
 pub struct Config {
+    pub command: Option<Command>,
     pub files: Vec<PathBuf>,
     pub glob: Option<String>,
     pub index: Option<String>,
+    pub index_format: IndexFormat,
+    pub index_cache: bool,
+    pub graph: Option<String>,
+    pub graph_format: GraphFormat,
     pub write: bool,
     pub md: bool,
     pub note: bool,
     pub json: bool,
     pub check: bool,
+    pub lint: bool,
+    pub lint_format: LintFormat,
+    pub lint_options: LintOptions,
+    pub unknown_values: bool,
+    pub validate: bool,
+    pub schema: Option<PathBuf>,
+    pub format: FormatOptions,
 }
 
 impl Config {
@@ -74,15 +242,84 @@ impl Config {
             vec![]
         };
 
+        let config_file = match &args.config {
+            Some(path) => ConfigFile::load(path)?,
+            None => ConfigFile::default(),
+        };
+
         Ok(Config {
+            command: args.command,
             files: [args.files.unwrap_or_default(), args.file, files].concat(),
             glob: args.glob,
             index: args.index,
+            index_format: args.index_format,
+            index_cache: !args.no_index_cache,
+            graph: args.graph,
+            graph_format: args.graph_format,
             write: args.write,
             md: args.md,
             note: args.note,
             json: args.json,
             check: args.check,
+            lint: args.lint,
+            lint_format: args.lint_format,
+            lint_options: config_file.lint,
+            unknown_values: args.unknown_values,
+            validate: args.validate,
+            schema: args.schema,
+            format: FormatOptions {
+                heading_level: args.heading_level,
+                slug_style: args.slug_style,
+                toc: TocOptions {
+                    auto: args.toc_auto,
+                    min_depth: args.toc_min_depth,
+                    max_depth: args.toc_max_depth,
+                    link: args.toc_link,
+                    style: args.toc_style,
+                    number_headings: args.number_headings,
+                },
+                journal: JournalOptions {
+                    patterns: [config_file.journal.patterns, args.journal_pattern].concat(),
+                    write: args.write_journal_date || config_file.journal.write,
+                },
+                index: IndexOptions {
+                    metadata_only: args.index_metadata_only || config_file.index.metadata_only,
+                    details: args.index_details || config_file.index.details,
+                },
+                status: config_file.status,
+                front_matter: FrontMatterOptions {
+                    key_order: if args.key_order.is_empty() {
+                        config_file.front_matter.key_order
+                    } else {
+                        args.key_order
+                    },
+                    keep_order: args.keep_key_order || config_file.front_matter.keep_order,
+                    format: args.front_matter_format.or(config_file.front_matter.format),
+                    timestamps: TimestampOptions {
+                        auto: args.timestamps || config_file.front_matter.timestamps.auto,
+                        created_from: args
+                            .created_from
+                            .unwrap_or(config_file.front_matter.timestamps.created_from),
+                        format: args
+                            .timestamp_format
+                            .or(config_file.front_matter.timestamps.format),
+                        date_format: args
+                            .date_format
+                            .or(config_file.front_matter.timestamps.date_format),
+                        input_formats: [
+                            config_file.front_matter.timestamps.input_formats,
+                            args.date_input_format,
+                        ]
+                        .concat(),
+                        timezone: args
+                            .timezone
+                            .or(config_file.front_matter.timestamps.timezone),
+                        default_timezone: args
+                            .default_timezone
+                            .or(config_file.front_matter.timestamps.default_timezone),
+                    },
+                },
+            },
         })
     }
 }
@@ -0,0 +1,27 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::lint::LintOptions;
+use crate::note::{FrontMatterOptions, IndexOptions, JournalOptions, StatusWorkflow};
+
+/// Settings read from `--config`, a TOML file.
+#[derive(PartialEq, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ConfigFile {
+    pub status: StatusWorkflow,
+    pub front_matter: FrontMatterOptions,
+    pub journal: JournalOptions,
+    pub index: IndexOptions,
+    pub lint: LintOptions,
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("could not read config `{}`", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("could not parse config `{}`", path.display()))
+    }
+}
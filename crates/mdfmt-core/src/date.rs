@@ -1,4 +1,29 @@
 mod flexible_date;
 mod flexible_date_time;
+mod parse;
 
-pub use {flexible_date::FlexibleDate, flexible_date_time::FlexibleDateTime};
+use chrono::{FixedOffset, Offset, Utc};
+
+pub use {
+    flexible_date::FlexibleDate,
+    flexible_date_time::FlexibleDateTime,
+    parse::{parse_date, parse_date_time, parse_timestamp},
+};
+
+/// How dates are read.
+#[derive(PartialEq, Debug, Clone)]
+pub struct DateOptions {
+    /// Offset of dates and times written without one.
+    pub offset: FixedOffset,
+    /// `strftime` formats tried before the built-in ones.
+    pub formats: Vec<String>,
+}
+
+impl Default for DateOptions {
+    fn default() -> Self {
+        Self {
+            offset: Utc.fix(),
+            formats: vec![],
+        }
+    }
+}
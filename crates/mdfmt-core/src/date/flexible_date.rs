@@ -1,10 +1,13 @@
 use std::fmt;
 
-use anyhow::{anyhow, Result};
-use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Utc};
+use anyhow::Result;
+use chrono::NaiveDate;
 use serde::{de, Deserializer, Serialize, Serializer};
 use serde_with::{DeserializeAs, SerializeAs};
 
+use super::{parse_date, parse_timestamp, DateOptions};
+
+/// Reads dates with the default `DateOptions`, like `FlexibleDateTime`.
 pub struct FlexibleDate;
 
 impl SerializeAs<NaiveDate> for FlexibleDate {
@@ -21,7 +24,7 @@ impl<'de> DeserializeAs<'de, NaiveDate> for FlexibleDate {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_str(DateVisitor)
+        deserializer.deserialize_any(DateVisitor)
     }
 }
 
@@ -38,32 +41,26 @@ impl<'de> de::Visitor<'de> for DateVisitor {
     where
         E: de::Error,
     {
-        if let Ok(t) =
-            DateTime::<FixedOffset>::parse_from_rfc3339(value).map(|dt| dt.with_timezone(&Utc))
-        {
-            if let Some(t) = NaiveDate::from_ymd_opt(t.year(), t.month(), t.day()) {
-                return Ok(t);
-            }
-        }
-        if let Ok(t) = NaiveDate::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
-            return Ok(t);
-        }
-        if let Ok(t) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
-            return Ok(t);
-        }
-        if let Ok(t) = parse_yyyy_mm(value) {
-            return Ok(t);
-        }
-
-        Err(E::custom(""))
+        parse_date(value, &DateOptions::default())
+            .ok_or_else(|| E::custom(format!("invalid date `{value}`")))
     }
-}
 
-fn parse_yyyy_mm(s: &str) -> Result<NaiveDate> {
-    let mut ss = s.split('-');
-    let year = ss.next().ok_or_else(|| anyhow!(""))?.parse::<i32>()?;
-    let month = ss.next().ok_or_else(|| anyhow!(""))?.parse::<u32>()?;
-    NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| anyhow!(""))
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        parse_timestamp(value, DateOptions::default().offset)
+            .map(|t| t.date_naive())
+            .ok_or_else(|| E::custom(format!("invalid timestamp `{value}`")))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let value = i64::try_from(value).map_err(E::custom)?;
+        self.visit_i64(value)
+    }
 }
 
 #[cfg(test)]
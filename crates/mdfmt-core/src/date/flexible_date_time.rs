@@ -1,10 +1,14 @@
 use std::fmt;
 
-use anyhow::{anyhow, Result};
-use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, SecondsFormat, Utc};
 use serde::{de, Deserializer, Serialize, Serializer};
 use serde_with::{DeserializeAs, SerializeAs};
 
+use super::{parse_date_time, parse_timestamp, DateOptions};
+
+/// Reads dates and times with the default `DateOptions`. Front matter has its dates read with
+/// the configured ones before, see `Meta::from_yaml`.
 pub struct FlexibleDateTime;
 
 impl SerializeAs<DateTime<Utc>> for FlexibleDateTime {
@@ -21,14 +25,33 @@ impl<'de> DeserializeAs<'de, DateTime<Utc>> for FlexibleDateTime {
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_str(DateTimeVisitor)
+        let t = deserializer.deserialize_any(DateTimeVisitor)?;
+        Ok(t.with_timezone(&Utc))
+    }
+}
+
+impl SerializeAs<DateTime<FixedOffset>> for FlexibleDateTime {
+    fn serialize_as<S>(value: &DateTime<FixedOffset>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&value.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+
+impl<'de> DeserializeAs<'de, DateTime<FixedOffset>> for FlexibleDateTime {
+    fn deserialize_as<D>(deserializer: D) -> Result<DateTime<FixedOffset>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(DateTimeVisitor)
     }
 }
 
 struct DateTimeVisitor;
 
 impl<'de> de::Visitor<'de> for DateTimeVisitor {
-    type Value = DateTime<Utc>;
+    type Value = DateTime<FixedOffset>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter.write_str("a formatted date and time string or a unix timestamp")
@@ -38,40 +61,25 @@ impl<'de> de::Visitor<'de> for DateTimeVisitor {
     where
         E: de::Error,
     {
-        if let Ok(t) =
-            DateTime::<FixedOffset>::parse_from_rfc3339(value).map(|dt| dt.with_timezone(&Utc))
-        {
-            return Ok(t);
-        }
-        if let Ok(t) = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
-            return Ok(DateTime::from_naive_utc_and_offset(t, Utc));
-        }
-        if let Ok(t) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
-            let t = t
-                .and_hms_opt(0, 0, 0)
-                .map(|t| DateTime::from_naive_utc_and_offset(t, Utc));
-            if let Some(t) = t {
-                return Ok(t);
-            }
-        }
-        if let Ok(t) = parse_yyyy_mm(value) {
-            let t = t
-                .and_hms_opt(0, 0, 0)
-                .map(|t| DateTime::from_naive_utc_and_offset(t, Utc));
-            if let Some(t) = t {
-                return Ok(t);
-            }
-        }
-
-        Err(E::custom(""))
+        parse_date_time(value, &DateOptions::default())
+            .ok_or_else(|| E::custom(format!("invalid date `{value}`")))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        parse_timestamp(value, DateOptions::default().offset)
+            .ok_or_else(|| E::custom(format!("invalid timestamp `{value}`")))
     }
-}
 
-fn parse_yyyy_mm(s: &str) -> Result<NaiveDate> {
-    let mut ss = s.split('-');
-    let year = ss.next().ok_or_else(|| anyhow!(""))?.parse::<i32>()?;
-    let month = ss.next().ok_or_else(|| anyhow!(""))?.parse::<u32>()?;
-    NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| anyhow!(""))
+    fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let value = i64::try_from(value).map_err(E::custom)?;
+        self.visit_i64(value)
+    }
 }
 
 #[cfg(test)]
@@ -129,4 +137,36 @@ mod tests {
         assert_eq!(expected, actual);
         Ok(())
     }
+
+    #[serde_as]
+    #[derive(PartialEq, Serialize, Deserialize, Debug)]
+    struct Bar {
+        #[serde_as(as = "Option<FlexibleDateTime>")]
+        date: Option<DateTime<FixedOffset>>,
+    }
+
+    #[test]
+    fn it_should_keep_offset() -> Result<()> {
+        let jst = FixedOffset::east_opt(9 * 3600).unwrap();
+        let expected = Bar {
+            date: Some(jst.with_ymd_and_hms(2000, 1, 1, 9, 0, 0).unwrap()),
+        };
+        let actual: Bar = serde_json::from_value(json!({ "date": "2000-01-01T09:00:00+09:00" }))?;
+        assert_eq!(expected, actual);
+        assert_eq!(
+            serde_json::to_value(&actual)?,
+            json!({ "date": "2000-01-01T09:00:00+09:00" })
+        );
+        assert_eq!(
+            serde_json::to_value(Bar {
+                date: Some(
+                    Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0)
+                        .unwrap()
+                        .fixed_offset()
+                ),
+            })?,
+            json!({ "date": "2000-01-01T00:00:00Z" })
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,173 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+
+use super::DateOptions;
+
+const DATE_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d"];
+
+/// Parses a date and time. Those without an offset are in `options.offset`.
+pub fn parse_date_time(s: &str, options: &DateOptions) -> Option<DateTime<FixedOffset>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t);
+    }
+
+    let with_offset = |t: NaiveDateTime| t.and_local_timezone(options.offset).single();
+    for format in &options.formats {
+        if let Ok(t) = DateTime::parse_from_str(s, format) {
+            return Some(t);
+        }
+        if let Ok(t) = NaiveDateTime::parse_from_str(s, format) {
+            return with_offset(t);
+        }
+        if let Ok(t) = NaiveDate::parse_from_str(s, format) {
+            return with_offset(t.and_hms_opt(0, 0, 0)?);
+        }
+    }
+
+    DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .or_else(|| parse_date(s, options)?.and_hms_opt(0, 0, 0))
+        .and_then(with_offset)
+}
+
+/// Parses a date, or the date part of a date and time as written.
+pub fn parse_date(s: &str, options: &DateOptions) -> Option<NaiveDate> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Some(t.date_naive());
+    }
+
+    for format in &options.formats {
+        if let Ok(t) = NaiveDate::parse_from_str(s, format) {
+            return Some(t);
+        }
+    }
+
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+        .or_else(|| parse_yyyymmdd(s))
+        .or_else(|| {
+            DATE_TIME_FORMATS
+                .iter()
+                .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+                .map(|t| t.date())
+        })
+        .or_else(|| parse_yyyy_mm(s))
+}
+
+/// Parses a unix timestamp, in `offset`. Eight digits that make a valid date are read as
+/// `yyyymmdd`.
+pub fn parse_timestamp(value: i64, offset: FixedOffset) -> Option<DateTime<FixedOffset>> {
+    if let Some(t) = parse_yyyymmdd(&value.to_string()) {
+        return t.and_hms_opt(0, 0, 0)?.and_local_timezone(offset).single();
+    }
+
+    DateTime::from_timestamp(value, 0).map(|t| t.with_timezone(&offset))
+}
+
+fn parse_yyyymmdd(s: &str) -> Option<NaiveDate> {
+    if s.len() != 8 || !s.bytes().all(|v| v.is_ascii_digit()) {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(
+        s[..4].parse().ok()?,
+        s[4..6].parse().ok()?,
+        s[6..].parse().ok()?,
+    )
+}
+
+fn parse_yyyy_mm(s: &str) -> Option<NaiveDate> {
+    let (year, month) = s.split_once('-')?;
+    NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Offset, TimeZone, Utc};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> Option<DateTime<FixedOffset>> {
+        Some(
+            Utc.with_ymd_and_hms(y, m, d, h, min, 0)
+                .unwrap()
+                .fixed_offset(),
+        )
+    }
+
+    #[test]
+    fn date_time_formats() {
+        let options = DateOptions::default();
+        let parse = |s| parse_date_time(s, &options);
+        assert_eq!(parse("2023/08/01"), utc(2023, 8, 1, 0, 0));
+        assert_eq!(parse("2023-08-01 10:00"), utc(2023, 8, 1, 10, 0));
+        assert_eq!(parse("2023/08/01 10:00"), utc(2023, 8, 1, 10, 0));
+        assert_eq!(parse("20230801"), utc(2023, 8, 1, 0, 0));
+        assert_eq!(parse("2023-08"), utc(2023, 8, 1, 0, 0));
+        assert_eq!(parse("yesterday"), None);
+    }
+
+    #[test]
+    fn date_formats() {
+        let options = DateOptions::default();
+        let parse = |s| parse_date(s, &options);
+        let expected = NaiveDate::from_ymd_opt(2023, 8, 1);
+        assert_eq!(parse("2023/08/01"), expected);
+        assert_eq!(parse("20230801"), expected);
+        assert_eq!(parse("2023-08-01 23:00"), expected);
+        assert_eq!(parse("2023-08-01T01:00:00+09:00"), expected);
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(
+            parse_timestamp(1690884000, Utc.fix()),
+            utc(2023, 8, 1, 10, 0)
+        );
+        assert_eq!(parse_timestamp(20230801, Utc.fix()), utc(2023, 8, 1, 0, 0));
+    }
+
+    #[test]
+    fn custom_formats() {
+        let options = DateOptions {
+            formats: vec!["%d.%m.%Y".into(), "%d.%m.%Y %H:%M".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_date("01.08.2023", &options),
+            NaiveDate::from_ymd_opt(2023, 8, 1)
+        );
+        assert_eq!(
+            parse_date_time("01.08.2023 10:00", &options),
+            utc(2023, 8, 1, 10, 0)
+        );
+        assert_eq!(parse_date("01.08.2023", &DateOptions::default()), None);
+    }
+
+    #[test]
+    fn default_offset() {
+        let jst = FixedOffset::east_opt(9 * 3600).unwrap();
+        let options = DateOptions {
+            offset: jst,
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_date_time("2000-01-01", &options),
+            jst.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).single()
+        );
+        assert_eq!(
+            parse_date_time("2000-01-01T00:00:00Z", &options),
+            utc(2000, 1, 1, 0, 0)
+        );
+    }
+}
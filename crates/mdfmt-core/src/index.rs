@@ -1,4 +1,19 @@
+mod cache;
+mod details;
+mod format;
+mod graph;
 mod indexes;
 mod item;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 
-pub use indexes::Indexes;
+pub(crate) use {
+    cache::{IndexCache, Lookup},
+    details::Details,
+    item::Item,
+};
+pub use {
+    format::IndexFormat,
+    graph::{Graph, GraphFormat},
+    indexes::Indexes,
+};
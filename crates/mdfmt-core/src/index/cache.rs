@@ -0,0 +1,188 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::item::Item;
+
+/// Items of an earlier run, so unchanged notes aren't parsed again.
+#[derive(PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct IndexCache {
+    /// Hash of the md-fmt version and options the items were built with.
+    key: String,
+    entries: BTreeMap<PathBuf, Entry>,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize)]
+struct Entry {
+    modified: SystemTime,
+    hash: String,
+    /// Whether the item's dates come from the file system.
+    derived: bool,
+    item: Item,
+}
+
+impl IndexCache {
+    /// An empty cache if the file is missing or was built with other options.
+    pub fn load(path: &Path, key: &str) -> Self {
+        let key = hash(key.as_bytes());
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|v| serde_json::from_str::<Self>(&v).ok())
+            .filter(|v| v.key == key)
+            .unwrap_or_else(|| Self {
+                key,
+                entries: BTreeMap::new(),
+            })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string(self)?;
+        fs::write(path, content)
+            .with_context(|| format!("could not write file `{}`", path.display()))
+    }
+
+    /// An empty cache built with the same options.
+    pub fn renew(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// The item of `file` if its modification time or content is unchanged. The file is read
+    /// only when its modification time differs.
+    pub fn take<F>(&mut self, file: &Path, modified: SystemTime, read: F) -> Result<Lookup>
+    where
+        F: FnOnce() -> Result<String>,
+    {
+        let entry = self.entries.remove(file);
+        if let Some(entry) = entry.as_ref().filter(|v| v.modified == modified) {
+            return Ok(Lookup::Hit {
+                item: Box::new(entry.item.clone()),
+                hash: entry.hash.clone(),
+                derived: entry.derived,
+            });
+        }
+
+        let content = read()?;
+        let hash = hash(content.as_bytes());
+        match entry {
+            Some(entry) if entry.hash == hash => Ok(Lookup::Hit {
+                item: Box::new(entry.item),
+                hash,
+                derived: entry.derived,
+            }),
+            _ => Ok(Lookup::Miss { content, hash }),
+        }
+    }
+
+    pub fn insert(
+        &mut self,
+        file: PathBuf,
+        modified: SystemTime,
+        hash: String,
+        derived: bool,
+        item: Item,
+    ) {
+        self.entries.insert(
+            file,
+            Entry {
+                modified,
+                hash,
+                derived,
+                item,
+            },
+        );
+    }
+}
+
+pub enum Lookup {
+    Hit {
+        item: Box<Item>,
+        hash: String,
+        derived: bool,
+    },
+    Miss {
+        content: String,
+        hash: String,
+    },
+}
+
+/// 64-bit FNV-1a, which is stable across builds unlike `DefaultHasher`.
+pub fn hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use anyhow::anyhow;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn item() -> Item {
+        Item {
+            file: "foo.md".into(),
+            path: "notes/foo.md".into(),
+            meta: None,
+            details: None,
+        }
+    }
+
+    fn cache(modified: SystemTime) -> IndexCache {
+        let mut cache = IndexCache::default();
+        cache.insert("notes/foo.md".into(), modified, hash(b"foo"), false, item());
+        cache
+    }
+
+    fn is_hit(lookup: Lookup) -> bool {
+        matches!(lookup, Lookup::Hit { item: v, .. } if *v == item())
+    }
+
+    #[test]
+    fn fnv_hash() {
+        assert_eq!(hash(b""), "cbf29ce484222325");
+        assert_eq!(hash(b"a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn unchanged_modified_time() -> Result<()> {
+        let modified = SystemTime::UNIX_EPOCH;
+        let lookup = cache(modified).take(Path::new("notes/foo.md"), modified, || {
+            Err(anyhow!("should not read the file"))
+        })?;
+        assert!(is_hit(lookup));
+        Ok(())
+    }
+
+    #[test]
+    fn unchanged_content() -> Result<()> {
+        let modified = SystemTime::UNIX_EPOCH;
+        let touched = modified + Duration::from_secs(1);
+        let lookup =
+            cache(modified).take(Path::new("notes/foo.md"), touched, || Ok("foo".into()))?;
+        assert!(is_hit(lookup));
+        Ok(())
+    }
+
+    #[test]
+    fn changed_content() -> Result<()> {
+        let modified = SystemTime::UNIX_EPOCH;
+        let touched = modified + Duration::from_secs(1);
+        let lookup =
+            cache(modified).take(Path::new("notes/foo.md"), touched, || Ok("bar".into()))?;
+        assert!(matches!(lookup, Lookup::Miss { hash: v, .. } if v == hash(b"bar")));
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use markdown::mdast::Node;
+use serde::{Deserialize, Serialize};
+
+use crate::note::{sections, Block, Note};
+
+/// What `--index-details` extracts from the body of a note.
+#[derive(PartialEq, Serialize, Deserialize, Debug, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Details {
+    pub outline: Vec<Heading>,
+    /// Words of prose and inline code. Code blocks, HTML and math are left out.
+    pub words: usize,
+    /// Characters of the same text, not counting whitespace.
+    pub characters: usize,
+    pub tasks: Tasks,
+    /// Number of cards of each kind.
+    pub cards: BTreeMap<String, usize>,
+    pub links: Vec<String>,
+    /// Notes linked with `[[target]]`.
+    pub wiki_links: Vec<String>,
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Heading {
+    pub depth: u8,
+    pub title: String,
+}
+
+#[derive(PartialEq, Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Tasks {
+    pub open: usize,
+    pub done: usize,
+}
+
+impl Details {
+    pub fn new(node: &Node, note: &Note) -> Self {
+        let mut res = Self {
+            outline: sections(&note.body)
+                .into_iter()
+                .map(|v| Heading {
+                    depth: v.depth,
+                    title: v.title.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        count_cards(&note.body, &mut res.cards);
+        for link in note.wiki_links.iter().filter(|v| !v.target.is_empty()) {
+            push_unique(&mut res.wiki_links, &link.target);
+        }
+        res.visit(node);
+        res
+    }
+
+    fn visit(&mut self, node: &Node) {
+        match node {
+            Node::InlineCode(v) => self.count(&v.value),
+            Node::Link(v) => push_unique(&mut self.links, &v.url),
+            Node::Definition(v) => push_unique(&mut self.links, &v.url),
+            Node::ListItem(v) => match v.checked {
+                Some(true) => self.tasks.done += 1,
+                Some(false) => self.tasks.open += 1,
+                None => {},
+            },
+            _ => {},
+        }
+
+        // Text next to a wiki link is a node of its own, but counted as one with it
+        let children = node.children().into_iter().flatten();
+        for (is_text, group) in &children.group_by(|v| matches!(v, Node::Text(_))) {
+            if is_text {
+                let text = group
+                    .filter_map(|v| match v {
+                        Node::Text(v) => Some(v.value.as_str()),
+                        _ => None,
+                    })
+                    .collect::<String>();
+                self.count(&text);
+            } else {
+                group.for_each(|v| self.visit(v));
+            }
+        }
+    }
+
+    fn count(&mut self, text: &str) {
+        self.words += text.split_whitespace().count();
+        self.characters += text.chars().filter(|v| !v.is_whitespace()).count();
+    }
+}
+
+fn count_cards(blocks: &[Block], res: &mut BTreeMap<String, usize>) {
+    for block in blocks {
+        match block {
+            Block::AnonymousSection(children) => count_cards(children, res),
+            Block::Section(section) => count_cards(&section.children, res),
+            Block::Card(card) => {
+                *res.entry(card.kind.to_string()).or_default() += 1;
+                count_cards(&card.children, res);
+            },
+            _ => {},
+        }
+    }
+}
+
+fn push_unique(list: &mut Vec<String>, value: &str) {
+    if !list.iter().any(|v| v == value) {
+        list.push(value.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{note::NoteParser, to_mdast_from_str};
+
+    #[test]
+    fn extract() -> anyhow::Result<()> {
+        let node = to_mdast_from_str(indoc! {"
+            # Foo
+
+            See [bar](bar.md), [[baz]] and [[qux#Heading|Qux]].
+
+            ## Tasks
+
+            - [ ] open `task`
+            - [x] done task
+        "})?;
+        let note = NoteParser::parse(&node)?;
+
+        assert_eq!(
+            Details::new(&node, &note),
+            Details {
+                outline: vec![
+                    Heading {
+                        depth: 1,
+                        title: "Foo".into(),
+                    },
+                    Heading {
+                        depth: 2,
+                        title: "Tasks".into(),
+                    },
+                ],
+                words: 12,
+                characters: 61,
+                tasks: Tasks { open: 1, done: 1 },
+                cards: BTreeMap::new(),
+                links: vec!["bar.md".into()],
+                wiki_links: vec!["baz".into(), "qux".into()],
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::{anyhow, Result};
+
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub enum IndexFormat {
+    /// One JSON array
+    #[default]
+    Json,
+    /// One JSON object per line
+    Ndjson,
+    /// One row per note, with lists joined by `, `
+    Csv,
+    /// A database with `notes`, `tags` and `authors` tables
+    Sqlite,
+}
+
+impl std::fmt::Display for IndexFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Ndjson => write!(f, "ndjson"),
+            Self::Csv => write!(f, "csv"),
+            Self::Sqlite => write!(f, "sqlite"),
+        }
+    }
+}
+
+impl std::str::FromStr for IndexFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            "csv" => Ok(Self::Csv),
+            "sqlite" => Ok(Self::Sqlite),
+            _ => Err(anyhow!("unknown index format `{s}`")),
+        }
+    }
+}
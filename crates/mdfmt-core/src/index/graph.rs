@@ -0,0 +1,304 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+
+use super::indexes::Indexes;
+use crate::{
+    links::{is_attachment, local_path, Resolver},
+    printer::Printer,
+};
+
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub enum GraphFormat {
+    #[default]
+    Json,
+    /// Graphviz
+    Dot,
+}
+
+impl std::fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Json => write!(f, "json"),
+            Self::Dot => write!(f, "dot"),
+        }
+    }
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "json" => Ok(Self::Json),
+            "dot" => Ok(Self::Dot),
+            _ => Err(anyhow!("unknown graph format `{s}`")),
+        }
+    }
+}
+
+/// Links between the notes of an index. Built from items with details.
+#[derive(PartialEq, Debug, Default, Serialize)]
+pub struct Graph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<Edge>,
+    /// Notes linking to each note.
+    pub backlinks: BTreeMap<String, Vec<String>>,
+    /// Notes without links to or from other notes.
+    pub orphans: Vec<String>,
+    /// Links to notes that aren't in the index.
+    pub unresolved: Vec<Unresolved>,
+}
+
+#[derive(PartialEq, Debug, Serialize)]
+pub struct GraphNode {
+    /// Path of the note.
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(PartialEq, Debug, Serialize)]
+pub struct Edge {
+    pub source: String,
+    pub target: String,
+    pub kind: LinkKind,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Wiki,
+    Markdown,
+}
+
+#[derive(PartialEq, Debug, Serialize)]
+pub struct Unresolved {
+    pub source: String,
+    pub link: String,
+}
+
+impl Graph {
+    pub fn new(indexes: &Indexes) -> Self {
+        let resolver = Resolver::new(indexes.data.iter().map(|v| &v.path));
+        let mut graph = Self::default();
+
+        for item in &indexes.data {
+            let id = id(&item.path);
+            graph.nodes.push(GraphNode {
+                id: id.clone(),
+                title: item
+                    .meta
+                    .as_ref()
+                    .and_then(|v| v.title.clone())
+                    .unwrap_or_else(|| stem(&item.path)),
+            });
+            graph.backlinks.insert(id, vec![]);
+        }
+
+        for item in &indexes.data {
+            let source = id(&item.path);
+            let Some(details) = &item.details else {
+                continue;
+            };
+            let wiki_links = details
+                .wiki_links
+                .iter()
+                .map(|v| (LinkKind::Wiki, v, v.clone(), resolver.wiki_link(v).map(id)));
+            let links = details
+                .links
+                .iter()
+                .filter_map(|v| Some((v, local_path(v)?)))
+                .map(|(v, path)| {
+                    let target = resolver.path(&item.path, &path).map(id);
+                    (LinkKind::Markdown, v, path, target)
+                });
+
+            for (kind, link, path, target) in wiki_links.chain(links) {
+                match target {
+                    Some(target) => graph.edges.push(Edge {
+                        source: source.clone(),
+                        target,
+                        kind,
+                    }),
+                    None if is_attachment(&path) => {},
+                    None => graph.unresolved.push(Unresolved {
+                        source: source.clone(),
+                        link: link.clone(),
+                    }),
+                }
+            }
+        }
+
+        let mut linked: BTreeSet<&str> = BTreeSet::new();
+        for edge in graph.edges.iter().filter(|v| v.source != v.target) {
+            linked.insert(&edge.source);
+            linked.insert(&edge.target);
+            let sources = graph.backlinks.entry(edge.target.clone()).or_default();
+            if !sources.contains(&edge.source) {
+                sources.push(edge.source.clone());
+            }
+        }
+        graph.orphans = graph
+            .nodes
+            .iter()
+            .filter(|v| !linked.contains(v.id.as_str()))
+            .map(|v| v.id.clone())
+            .collect();
+
+        graph
+    }
+
+    fn print_dot(&self) -> String {
+        let mut res = String::from("digraph notes {\n");
+        for node in &self.nodes {
+            res.push_str(&format!(
+                "    {} [label={}];\n",
+                quote(&node.id),
+                quote(&node.title)
+            ));
+        }
+        for edge in &self.edges {
+            res.push_str(&format!(
+                "    {} -> {};\n",
+                quote(&edge.source),
+                quote(&edge.target)
+            ));
+        }
+        res.push_str("}\n");
+        res
+    }
+}
+
+impl Printer for Graph {
+    type Options = GraphFormat;
+
+    fn print(&self, options: Self::Options) -> Result<String> {
+        match options {
+            GraphFormat::Json => Ok(serde_json::to_string_pretty(self)?),
+            GraphFormat::Dot => Ok(self.print_dot()),
+        }
+    }
+}
+
+fn id(path: &Path) -> String {
+    path.display().to_string()
+}
+
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|v| v.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::index::{Details, Item};
+
+    fn item(path: &str, wiki_links: &[&str], links: &[&str]) -> Item {
+        Item {
+            file: stem(Path::new(path)),
+            path: path.into(),
+            meta: None,
+            details: Some(Details {
+                wiki_links: wiki_links.iter().map(|v| v.to_string()).collect(),
+                links: links.iter().map(|v| v.to_string()).collect(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn graph() -> Graph {
+        Graph::new(&Indexes::new(vec![
+            item("a.md", &["B", "image.png", "missing"], &[]),
+            item(
+                "dir/b.md",
+                &[],
+                &["../a.md#top", "./c%20d.md", "https://example.com"],
+            ),
+            item("dir/c d.md", &[], &[]),
+            item("e.md", &[], &[]),
+        ]))
+    }
+
+    #[test]
+    fn resolve_links() {
+        let graph = graph();
+        assert_eq!(
+            graph
+                .edges
+                .iter()
+                .map(|v| (v.source.as_str(), v.target.as_str(), v.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("a.md", "dir/b.md", LinkKind::Wiki),
+                ("dir/b.md", "a.md", LinkKind::Markdown),
+                ("dir/b.md", "dir/c d.md", LinkKind::Markdown),
+            ]
+        );
+        assert_eq!(
+            graph.unresolved,
+            vec![Unresolved {
+                source: "a.md".into(),
+                link: "missing".into(),
+            }]
+        );
+        assert_eq!(graph.backlinks["dir/b.md"], vec!["a.md"]);
+        assert_eq!(graph.backlinks["e.md"], Vec::<String>::new());
+        assert_eq!(graph.orphans, vec!["e.md"]);
+    }
+
+    #[test]
+    fn dotted_names() {
+        let graph = Graph::new(&Indexes::new(vec![
+            item(
+                "a.md",
+                &["v1.2 notes", "v2.0 plan", "photo.JPG"],
+                &["v1.2%20notes"],
+            ),
+            item("v1.2 notes.md", &[], &[]),
+        ]));
+        assert_eq!(
+            graph
+                .edges
+                .iter()
+                .map(|v| (v.target.as_str(), v.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("v1.2 notes.md", LinkKind::Wiki),
+                ("v1.2 notes.md", LinkKind::Markdown),
+            ]
+        );
+        assert_eq!(
+            graph.unresolved,
+            vec![Unresolved {
+                source: "a.md".into(),
+                link: "v2.0 plan".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn print_dot() -> Result<()> {
+        let graph = Graph::new(&Indexes::new(vec![
+            item("a.md", &["b"], &[]),
+            item("b.md", &[], &[]),
+        ]));
+        assert_eq!(
+            graph.print(GraphFormat::Dot)?,
+            "digraph notes {\n    \"a.md\" [label=\"a\"];\n    \"b.md\" [label=\"b\"];\n    \"a.md\" -> \"b.md\";\n}\n"
+        );
+        Ok(())
+    }
+}
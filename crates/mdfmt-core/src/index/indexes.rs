@@ -1,8 +1,11 @@
-use std::path::PathBuf;
+use std::{fs, path::Path, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 
-use super::item::Item;
+use super::{
+    format::IndexFormat,
+    item::{Item, COLUMNS},
+};
 use crate::{note::Note, printer::Printer};
 
 pub struct Indexes {
@@ -17,12 +20,37 @@ impl Indexes {
     pub fn push(&mut self, path: &PathBuf, note: &Note) {
         self.data.push(Item::new(path, note));
     }
-}
 
-impl Printer for Indexes {
-    type Options = ();
+    pub(crate) fn push_item(&mut self, item: Item) {
+        self.data.push(item);
+    }
+
+    /// Notes that go into the index.
+    pub(super) fn items(&self) -> impl Iterator<Item = &Item> {
+        self.data.iter().filter(|v| v.meta.is_some())
+    }
+
+    pub fn write(&self, file: &Path, format: IndexFormat) -> Result<()> {
+        if format == IndexFormat::Sqlite {
+            return self.write_sqlite(file);
+        }
+
+        let content = self.print(format)?;
+        fs::write(file, content)
+            .with_context(|| format!("could not write file `{}`", file.display()))
+    }
+
+    #[cfg(feature = "sqlite")]
+    fn write_sqlite(&self, file: &Path) -> Result<()> {
+        super::sqlite::write(self, file)
+    }
+
+    #[cfg(not(feature = "sqlite"))]
+    fn write_sqlite(&self, _file: &Path) -> Result<()> {
+        Err(anyhow!("md-fmt was built without the `sqlite` feature"))
+    }
 
-    fn print(&self, _options: Self::Options) -> Result<String> {
+    fn print_json(&self) -> Result<String> {
         let mut res = String::from("[");
         let mut first = true;
 
@@ -40,4 +68,80 @@ impl Printer for Indexes {
 
         Ok(res)
     }
+
+    fn print_ndjson(&self) -> Result<String> {
+        let mut res = String::new();
+        for item in &self.data {
+            if let Some(json) = item.to_json()? {
+                res.push_str(&json);
+                res.push('\n');
+            }
+        }
+        Ok(res)
+    }
+
+    fn print_csv(&self) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.write_record(COLUMNS)?;
+        for item in self.items() {
+            writer.write_record(item.to_row()?)?;
+        }
+        let res = writer.into_inner().map_err(|e| anyhow!(e.to_string()))?;
+        Ok(String::from_utf8(res)?)
+    }
+}
+
+impl Printer for Indexes {
+    type Options = IndexFormat;
+
+    fn print(&self, options: Self::Options) -> Result<String> {
+        match options {
+            IndexFormat::Json => self.print_json(),
+            IndexFormat::Ndjson => self.print_ndjson(),
+            IndexFormat::Csv => self.print_csv(),
+            IndexFormat::Sqlite => Err(anyhow!("a SQLite index can only be written to a file")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::note::{Meta, Metadata};
+
+    fn indexes() -> Indexes {
+        let mut indexes = Indexes::new(vec![]);
+        let note = Note::new(
+            Some(Metadata::Meta(Meta {
+                title: Some("foo, bar".into()),
+                tags: Some(vec!["a".into(), "b".into()]),
+                ..Default::default()
+            })),
+            vec![],
+        );
+        indexes.push(&PathBuf::from("notes/foo.md"), &note);
+        indexes.push(&PathBuf::from("notes/bar.md"), &Note::default());
+        indexes
+    }
+
+    #[test]
+    fn print_ndjson() -> Result<()> {
+        assert_eq!(
+            indexes().print(IndexFormat::Ndjson)?,
+            "{\"file\":\"foo.md\",\"path\":\"notes/foo.md\",\"meta\":{\"title\":\"foo, bar\",\"tags\":[\"a\",\"b\"]}}\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn print_csv() -> Result<()> {
+        assert_eq!(
+            indexes().print(IndexFormat::Csv)?,
+            "file,path,title,description,link,status,kind,journalDate,createdAt,updatedAt,author,tags\n\
+             foo.md,notes/foo.md,\"foo, bar\",,,,,,,,,\"a, b\"\n"
+        );
+        Ok(())
+    }
 }
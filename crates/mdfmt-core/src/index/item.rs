@@ -2,14 +2,34 @@ use std::path::PathBuf;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use super::details::Details;
 use crate::note::{Meta, Metadata, Note};
 
-#[derive(PartialEq, Serialize, Deserialize, Debug)]
+/// Columns of CSV and SQLite indexes. Other front matter keys are left out.
+pub const COLUMNS: &[&str] = &[
+    "file",
+    "path",
+    "title",
+    "description",
+    "link",
+    "status",
+    "kind",
+    "journalDate",
+    "createdAt",
+    "updatedAt",
+    "author",
+    "tags",
+];
+
+#[derive(PartialEq, Serialize, Deserialize, Debug, Clone)]
 pub struct Item {
     pub file: String,
     pub path: PathBuf,
     pub meta: Option<Meta>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Details>,
 }
 
 impl Item {
@@ -27,6 +47,14 @@ impl Item {
                 Some(Metadata::Meta(v)) => Some(v.clone()),
                 _ => None,
             },
+            details: None,
+        }
+    }
+
+    pub fn with_details(self, details: Details) -> Self {
+        Self {
+            details: Some(details),
+            ..self
         }
     }
 
@@ -39,4 +67,32 @@ impl Item {
             Ok(Some(res))
         }
     }
+
+    /// Values of `COLUMNS`, with lists joined by `, `.
+    pub fn to_row(&self) -> Result<Vec<String>> {
+        let meta = serde_json::to_value(&self.meta)
+            .with_context(|| format!("could not print file `{}`", self.path.display()))?;
+
+        Ok(COLUMNS
+            .iter()
+            .map(|column| match *column {
+                "file" => self.file.clone(),
+                "path" => self.path.display().to_string(),
+                key => to_cell(meta.get(key)),
+            })
+            .collect())
+    }
+}
+
+fn to_cell(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(v)) => v.clone(),
+        Some(Value::Array(v)) => v
+            .iter()
+            .map(|v| to_cell(Some(v)))
+            .collect::<Vec<String>>()
+            .join(", "),
+        Some(v) => v.to_string(),
+    }
 }
@@ -0,0 +1,122 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rusqlite::{params, params_from_iter, Connection};
+
+use super::{indexes::Indexes, item::COLUMNS};
+
+const SCHEMA: &str = "
+CREATE TABLE notes (
+    id INTEGER PRIMARY KEY,
+    file TEXT NOT NULL,
+    path TEXT NOT NULL,
+    title TEXT,
+    description TEXT,
+    link TEXT,
+    status TEXT,
+    kind TEXT,
+    journal_date TEXT,
+    created_at TEXT,
+    updated_at TEXT,
+    meta TEXT NOT NULL
+);
+CREATE TABLE tags (
+    note_id INTEGER NOT NULL REFERENCES notes (id),
+    tag TEXT NOT NULL
+);
+CREATE TABLE authors (
+    note_id INTEGER NOT NULL REFERENCES notes (id),
+    author TEXT NOT NULL
+);
+CREATE INDEX tags_tag ON tags (tag);
+CREATE INDEX authors_author ON authors (author);
+";
+
+/// Writes the index as a new database, replacing `file`.
+pub fn write(indexes: &Indexes, file: &Path) -> Result<()> {
+    if file.exists() {
+        fs::remove_file(file)
+            .with_context(|| format!("could not replace file `{}`", file.display()))?;
+    }
+    let mut conn = Connection::open(file)
+        .with_context(|| format!("could not create database `{}`", file.display()))?;
+    conn.execute_batch(SCHEMA)?;
+
+    let tx = conn.transaction()?;
+    {
+        let mut insert_note = tx.prepare(
+            "INSERT INTO notes (file, path, title, description, link, status, kind, journal_date, created_at, updated_at, meta)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?;
+        let mut insert_tag = tx.prepare("INSERT INTO tags (note_id, tag) VALUES (?1, ?2)")?;
+        let mut insert_author =
+            tx.prepare("INSERT INTO authors (note_id, author) VALUES (?1, ?2)")?;
+
+        for item in indexes.items() {
+            // Empty cells become NULL, and lists go into their own tables
+            let row = item.to_row()?;
+            let values = row
+                .iter()
+                .zip(COLUMNS)
+                .filter(|(_, column)| !matches!(**column, "author" | "tags"))
+                .map(|(v, _)| Some(v).filter(|v| !v.is_empty()));
+            let meta = serde_json::to_string(&item.meta)?;
+            insert_note.execute(params_from_iter(values.chain([Some(&meta)])))?;
+
+            let id = tx.last_insert_rowid();
+            let meta = item.meta.as_ref();
+            for tag in meta.and_then(|v| v.tags.as_ref()).into_iter().flatten() {
+                insert_tag.execute(params![id, tag])?;
+            }
+            for author in meta.and_then(|v| v.author.as_ref()).into_iter().flatten() {
+                insert_author.execute(params![id, author])?;
+            }
+        }
+    }
+    tx.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::note::{Meta, Metadata, Note};
+
+    #[test]
+    fn write_database() -> Result<()> {
+        let mut indexes = Indexes::new(vec![]);
+        let note = Note::new(
+            Some(Metadata::Meta(Meta {
+                title: Some("foo".into()),
+                tags: Some(vec!["a".into(), "b".into()]),
+                ..Default::default()
+            })),
+            vec![],
+        );
+        indexes.push(&PathBuf::from("notes/foo.md"), &note);
+
+        let temp = tempfile::tempdir()?;
+        let file = temp.path().join("index.db");
+        write(&indexes, &file)?;
+        let conn = Connection::open(&file)?;
+        let rows = conn
+            .prepare(
+                "SELECT title, tag FROM notes JOIN tags ON tags.note_id = notes.id ORDER BY tag",
+            )?
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        assert_eq!(
+            rows,
+            vec![("foo".into(), "a".into()), ("foo".into(), "b".into())]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+/// Reads a date from a path matching one of `patterns`, such as `{year}-{month}-{day}.md`
+/// or `journal/{year}/{month}/{day}.md`. Patterns match the end of the path.
+pub fn journal_date(path: &Path, patterns: &[String]) -> Option<NaiveDate> {
+    let path = path.to_str()?.replace('\\', "/");
+    patterns.iter().find_map(|pattern| {
+        let captures = to_regex(pattern)?.captures(&path)?;
+        NaiveDate::from_ymd_opt(
+            captures.name("year")?.as_str().parse().ok()?,
+            captures.name("month")?.as_str().parse().ok()?,
+            captures.name("day")?.as_str().parse().ok()?,
+        )
+    })
+}
+
+fn to_regex(pattern: &str) -> Option<Regex> {
+    let pattern = regex::escape(pattern)
+        .replace(r"\{year\}", r"(?P<year>\d{4})")
+        .replace(r"\{month\}", r"(?P<month>\d{1,2})")
+        .replace(r"\{day\}", r"(?P<day>\d{1,2})");
+    Regex::new(&format!("(?:^|/){pattern}$")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn from_path() {
+        let patterns = vec![
+            "{year}-{month}-{day}.md".to_string(),
+            "journal/{year}/{month}/{day}.md".to_string(),
+        ];
+        let expected = NaiveDate::from_ymd_opt(2023, 8, 1);
+        assert_eq!(
+            journal_date(Path::new("notes/2023-08-01.md"), &patterns),
+            expected
+        );
+        assert_eq!(
+            journal_date(Path::new("vault/journal/2023/08/01.md"), &patterns),
+            expected
+        );
+        assert_eq!(journal_date(Path::new("x2023-08-01.md"), &patterns), None);
+        assert_eq!(journal_date(Path::new("2023-13-01.md"), &patterns), None);
+    }
+}
@@ -4,12 +4,19 @@ mod cli;
 mod date;
 mod debug_printer;
 mod index;
+mod journal;
+mod links;
+mod lint;
 mod note;
 mod printer;
+mod rename;
+mod schema;
+mod timestamps;
 mod typescript_custom_section;
 
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Context, Result};
 use glob::glob;
@@ -18,8 +25,21 @@ use markdown::{to_mdast, Constructs, ParseOptions};
 use once_cell::sync::Lazy;
 use regex::{Regex, RegexBuilder};
 
-pub use crate::{ast::builder, cli::Config, index::Indexes, note::*};
-use crate::{debug_printer::DebugPrinter, printer::Printer};
+pub use crate::{
+    ast::{builder, wiki_link},
+    cli::{Command, Config},
+    index::{Graph, GraphFormat, IndexFormat, Indexes},
+    lint::{Diagnostic, Finding, LintFormat, LintInput, LintOptions, Linter, Rule, Severity},
+    note::*,
+    schema::{Schema, Violation},
+};
+use crate::{
+    debug_printer::DebugPrinter,
+    index::{Details, IndexCache, Item, Lookup},
+    links::{check_links, Problem, Resolver},
+    printer::Printer,
+    rename::{Edit, Rename},
+};
 
 static RE: Lazy<Regex> = Lazy::new(|| {
     RegexBuilder::new(r"\[!\[[^]]*\]\([^)]*\)[^]]*\]\([^)]*\)")
@@ -29,17 +49,21 @@ static RE: Lazy<Regex> = Lazy::new(|| {
 });
 
 pub fn format(input: &str) -> Result<String> {
+    format_with(input, &FormatOptions::default())
+}
+
+pub fn format_with(input: &str, options: &FormatOptions) -> Result<String> {
     let node = to_mdast_from_str(input).with_context(|| anyhow!("could not parse file"))?;
-    print_node(&node)
+    print_node(input, &node, options)
 }
 
 pub fn parse(input: &str) -> Result<Note> {
     let node = to_mdast_from_str(input).with_context(|| anyhow!("could not parse"))?;
-    NoteParser::parse(&node)
+    parse_note(input, &node, &FormatOptions::default())
 }
 
 pub fn stringify(input: &Note) -> Result<String> {
-    input.print(())
+    input.print(FormatOptions::default())
 }
 
 pub fn stringify_block(input: &Block) -> Result<String> {
@@ -52,9 +76,13 @@ fn escape(s: &str) -> String {
     RE.replace_all(s, "`$0`").to_string()
 }
 
+/// Parses a note after its JSON front matter, which `markdown` doesn't know. See
+/// `parse_note` for the whole note.
 fn to_mdast_from_str(s: &str) -> Result<Node> {
-    to_mdast(
-        &escape(s),
+    let (_, s) = split_json_front_matter(s);
+    let s = escape(s);
+    let mut node = to_mdast(
+        &s,
         &ParseOptions {
             constructs: Constructs {
                 frontmatter: true,
@@ -65,14 +93,71 @@ fn to_mdast_from_str(s: &str) -> Result<Node> {
             ..ParseOptions::gfm()
         },
     )
-    .map_err(|s| anyhow!(s))
+    .map_err(|s| anyhow!(s))?;
+    wiki_link::split(&mut node, &s);
+    Ok(node)
+}
+
+/// JSON front matter, split off before parsing the rest of a note.
+#[derive(PartialEq, Debug)]
+pub(crate) struct JsonFrontMatter<'a> {
+    /// The JSON object.
+    pub value: &'a str,
+    /// Whether it was written between `;;;` lines rather than as a bare object.
+    pub delimited: bool,
+}
+
+/// Splits off JSON front matter, either a leading object or one between `;;;` lines.
+fn split_json_front_matter(s: &str) -> (Option<JsonFrontMatter<'_>>, &str) {
+    if let Some(rest) = s.strip_prefix(";;;\n") {
+        if let Some(end) = rest.find("\n;;;") {
+            let after = &rest[end + 4..];
+            if after.is_empty() || after.starts_with('\n') {
+                let json = JsonFrontMatter {
+                    value: &rest[..end],
+                    delimited: true,
+                };
+                return (Some(json), after.strip_prefix('\n').unwrap_or(after));
+            }
+        }
+    }
+
+    if s.starts_with('{') {
+        let mut iter = serde_json::Deserializer::from_str(s)
+            .into_iter::<serde_json::Map<String, serde_json::Value>>();
+        if let Some(Ok(_)) = iter.next() {
+            let end = iter.byte_offset();
+            let after = &s[end..];
+            if after.is_empty() || after.starts_with('\n') {
+                let json = JsonFrontMatter {
+                    value: &s[..end],
+                    delimited: false,
+                };
+                return (Some(json), after.strip_prefix('\n').unwrap_or(after));
+            }
+        }
+    }
+
+    (None, s)
+}
+
+/// Parses `node`, the mdast of `content`, together with the JSON front matter of `content`.
+fn parse_note(content: &str, node: &Node, options: &FormatOptions) -> Result<Note> {
+    let (json, _) = split_json_front_matter(content);
+    NoteParser::parse_with_json(node, json.as_ref(), options)
 }
 
-fn print_node(node: &Node) -> Result<String> {
-    NoteParser::parse(node)?.normalize()?.print(())
+fn print_node(content: &str, node: &Node, options: &FormatOptions) -> Result<String> {
+    parse_note(content, node, options)?
+        .normalize(options)?
+        .print(options.clone())
 }
 
 pub fn run(config: &Config) -> Result<()> {
+    if let Some(Command::Status { file, status }) = &config.command {
+        return set_status(config, file, status);
+    }
+
     let entries: Vec<PathBuf> = if let Some(pattern) = &config.glob {
         (glob(pattern)?)
             .flatten()
@@ -82,12 +167,82 @@ pub fn run(config: &Config) -> Result<()> {
         config.files.clone()
     };
 
-    if let Some(file) = &config.index {
-        let content = generate_index(&entries)?;
+    match &config.command {
+        Some(Command::Mv { old, new, dry_run }) => {
+            return move_note(config, &vault_files(entries)?, old, new, *dry_run);
+        },
+        Some(Command::Links) => {
+            let problems = check_links(&vault_files(entries)?, &config.format)?;
+            for Problem {
+                file,
+                line,
+                message,
+            } in &problems
+            {
+                println!("{}:{line}: {message}", file.display());
+            }
+            if !problems.is_empty() {
+                return Err(anyhow!("found {} link problem(s)", problems.len()));
+            }
+            return Ok(());
+        },
+        _ => {},
+    }
+
+    if config.unknown_values {
+        for ((key, value), files) in find_unknown_values(&entries, &config.format.status)? {
+            let files = files
+                .iter()
+                .map(|v| v.display().to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+            println!("{key}: unknown value `{value}` ({files})");
+        }
+        return Ok(());
+    }
+
+    if config.validate {
+        let Some(path) = &config.schema else {
+            return Err(anyhow!("`--validate` needs a `--schema`"));
+        };
+        let schema = Schema::load(path)?;
+        let mut count = 0;
+        for entry in &entries {
+            for Violation { key, message } in validate_file(&schema, entry)? {
+                if key.is_empty() {
+                    println!("{}: {message}", entry.display());
+                } else {
+                    println!("{}: {key}: {message}", entry.display());
+                }
+                count += 1;
+            }
+        }
+        if count > 0 {
+            return Err(anyhow!("found {count} front matter violation(s)"));
+        }
+        return Ok(());
+    }
+
+    if config.lint {
+        return run_lint(config, &entries);
+    }
+
+    if let Some(file) = &config.graph {
+        let content = generate_graph(&entries, &config.format)?.print(config.graph_format)?;
         fs::write(file, content).with_context(|| format!("could not write file `{}`", file))?;
         return Ok(());
     }
 
+    if let Some(file) = &config.index {
+        let indexes = if config.index_cache {
+            build_index_cached(&entries, &config.format, &index_cache_path(file))?
+        } else {
+            build_index(&entries, &config.format)?
+        };
+        indexes.write(Path::new(file), config.index_format)?;
+        return Ok(());
+    }
+
     for entry in entries {
         run_file(config, &entry)?;
     }
@@ -96,8 +251,114 @@ pub fn run(config: &Config) -> Result<()> {
 }
 
 pub fn generate_index(files: &[PathBuf]) -> Result<String> {
+    generate_index_with(files, &FormatOptions::default())
+}
+
+pub fn generate_index_with(files: &[PathBuf], options: &FormatOptions) -> Result<String> {
+    build_index(files, options)?.print(IndexFormat::Json)
+}
+
+pub fn build_index(files: &[PathBuf], options: &FormatOptions) -> Result<Indexes> {
     let mut indexes = Indexes::new(vec![]);
 
+    for file in files {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("could not read file `{}`", file.display()))?;
+        indexes.push_item(index_item(file, &content, options)?.0);
+    }
+
+    Ok(indexes)
+}
+
+/// Links between `files`. Notes without front matter are included.
+pub fn generate_graph(files: &[PathBuf], options: &FormatOptions) -> Result<Graph> {
+    let options = FormatOptions {
+        index: IndexOptions {
+            metadata_only: false,
+            details: true,
+        },
+        ..options.clone()
+    };
+    Ok(Graph::new(&build_index(files, &options)?))
+}
+
+/// Like `build_index`, but parses only the files that changed since the run that wrote
+/// `cache`.
+pub fn build_index_cached(
+    files: &[PathBuf],
+    options: &FormatOptions,
+    cache: &Path,
+) -> Result<Indexes> {
+    let key = format!("{} {options:?}", env!("CARGO_PKG_VERSION"));
+    let mut previous = IndexCache::load(cache, &key);
+    let mut next = previous.renew();
+    let mut indexes = Indexes::new(vec![]);
+
+    for file in files {
+        let modified = fs::metadata(file)
+            .and_then(|v| v.modified())
+            .with_context(|| format!("could not read metadata of `{}`", file.display()))?;
+        let read = || {
+            fs::read_to_string(file)
+                .with_context(|| format!("could not read file `{}`", file.display()))
+        };
+        let (item, hash, derived) = match previous.take(file, modified, read)? {
+            Lookup::Hit {
+                mut item,
+                hash,
+                derived,
+            } => {
+                // Dates from the file system change even if the content doesn't
+                if let Some(meta) = item.meta.as_mut().filter(|_| derived) {
+                    derive_dates(meta, file, &options.front_matter.timestamps)?;
+                }
+                (*item, hash, derived)
+            },
+            Lookup::Miss { content, hash } => {
+                let (item, derived) = index_item(file, &content, options)?;
+                (item, hash, derived)
+            },
+        };
+        next.insert(file.clone(), modified, hash, derived, item.clone());
+        indexes.push_item(item);
+    }
+
+    next.save(cache)?;
+    Ok(indexes)
+}
+
+fn index_cache_path(index: &str) -> PathBuf {
+    PathBuf::from(format!("{index}.cache"))
+}
+
+/// The index item of a note, and whether its meta was derived from the file.
+fn index_item(file: &PathBuf, content: &str, options: &FormatOptions) -> Result<(Item, bool)> {
+    let node = to_mdast_from_str(content)
+        .with_context(|| format!("could not parse file `{}`", file.display()))?;
+
+    let mut note = parse_note(content, &node, options)?.normalize(options)?;
+    // Front matter created here would put a note without any in a `metadata_only` index
+    let derive = note.metadata.is_none() && !options.index.metadata_only;
+    if note.metadata.is_some() || derive {
+        fill_journal_date(&mut note, file, &options.journal);
+    }
+    if derive {
+        derive_meta(&mut note, file, &options.front_matter.timestamps)?;
+    }
+    let item = Item::new(file, &note);
+    if options.index.details {
+        return Ok((item.with_details(Details::new(&node, &note)), derive));
+    }
+    Ok((item, derive))
+}
+
+/// Groups files by the unrecognized `status` and `kind` values in their front matter.
+pub fn find_unknown_values(
+    files: &[PathBuf],
+    workflow: &StatusWorkflow,
+) -> Result<BTreeMap<(String, String), Vec<PathBuf>>> {
+    let mut res: BTreeMap<(String, String), Vec<PathBuf>> = BTreeMap::new();
+
     for file in files {
         let content = fs::read_to_string(file)
             .with_context(|| format!("could not read file `{}`", file.display()))?;
@@ -105,11 +366,233 @@ pub fn generate_index(files: &[PathBuf]) -> Result<String> {
         let node = to_mdast_from_str(&content)
             .with_context(|| format!("could not parse file `{}`", file.display()))?;
 
-        let note = NoteParser::parse(&node)?.normalize()?;
-        indexes.push(file, &note);
+        let note = parse_note(&content, &node, &FormatOptions::default())?;
+        if let Some(Metadata::Meta(meta)) = note.metadata {
+            for (key, value) in meta.unknown_values() {
+                if key == "status" && workflow.is_known(&NoteStatus::Other(value.to_string())) {
+                    continue;
+                }
+                res.entry((key.to_string(), value.to_string()))
+                    .or_default()
+                    .push(file.clone());
+            }
+        }
     }
 
-    indexes.print(())
+    Ok(res)
+}
+
+pub fn validate_file(schema: &Schema, file: &PathBuf) -> Result<Vec<Violation>> {
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("could not read file `{}`", file.display()))?;
+    validate(schema, &content).with_context(|| format!("could not parse file `{}`", file.display()))
+}
+
+/// Checks the front matter of a note, in YAML, TOML or JSON, against `schema`.
+pub fn validate(schema: &Schema, content: &str) -> Result<Vec<Violation>> {
+    let node = to_mdast_from_str(content)?;
+
+    let front_matter = match (
+        split_json_front_matter(content),
+        node.children().map(Vec::as_slice),
+    ) {
+        ((Some(json), _), _) => {
+            serde_json::from_str::<serde_json::Value>(json.value).map_err(|err| err.to_string())
+        },
+        (_, Some([Node::Yaml(node), ..])) => {
+            serde_yaml::from_str::<serde_json::Value>(&node.value).map_err(|err| err.to_string())
+        },
+        (_, Some([Node::Toml(node), ..])) => {
+            toml::from_str::<serde_json::Value>(&node.value).map_err(|err| err.to_string())
+        },
+        _ => return Ok(schema.validate(None)),
+    };
+
+    match front_matter {
+        Ok(value) => Ok(schema.validate(Some(&value))),
+        Err(err) => Ok(vec![Violation::new(
+            "",
+            &format!("invalid front matter: {err}"),
+        )]),
+    }
+}
+
+/// Moves a note to `status` if the workflow allows it, and updates `updatedAt` if it changed.
+pub fn set_status(config: &Config, file: &PathBuf, status: &str) -> Result<()> {
+    let content = fs::read_to_string(file)
+        .with_context(|| format!("could not read file `{}`", file.display()))?;
+
+    let node = to_mdast_from_str(&content)
+        .with_context(|| format!("could not parse file `{}`", file.display()))?;
+
+    let mut note = parse_note(&content, &node, &config.format)?;
+    let mut meta = match note.metadata {
+        Some(Metadata::Meta(meta)) => meta,
+        Some(Metadata::Raw(_) | Metadata::RawToml(_) | Metadata::RawJson(_)) => {
+            return Err(anyhow!(
+                "could not parse front matter of `{}`",
+                file.display()
+            ))
+        },
+        None => Meta::default(),
+    };
+    let workflow = &config.format.status;
+    let next = workflow
+        .transition(meta.status.as_ref(), status)
+        .with_context(|| format!("could not change status of `{}`", file.display()))?;
+    if meta.status.clone().map(|v| workflow.resolve(v)) != Some(next.clone()) {
+        let offset = config.format.front_matter.timestamps.default_offset();
+        meta.updated_at = Some(timestamps::now(offset));
+    }
+    meta.status = Some(next);
+    note.metadata = Some(Metadata::Meta(meta));
+
+    let content = note
+        .normalize(&config.format)?
+        .print(config.format.clone())
+        .with_context(|| format!("could not stringify file `{}`", file.display()))?;
+    fs::write(file, content).with_context(|| format!("could not write file `{}`", file.display()))
+}
+
+/// Prints the lint diagnostics of `files`. Fails if any has the `error` severity.
+fn run_lint(config: &Config, files: &[PathBuf]) -> Result<()> {
+    let linter = Linter::new(&config.lint_options)?;
+    let mut diagnostics: Vec<Diagnostic> = vec![];
+    for file in files {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("could not read file `{}`", file.display()))?;
+        let node = to_mdast_from_str(&content)
+            .with_context(|| format!("could not parse file `{}`", file.display()))?;
+        let note = parse_note(&content, &node, &config.format)?;
+        diagnostics.extend(linter.lint(file, &content, &node, &note, &config.format));
+    }
+
+    match config.lint_format {
+        LintFormat::Text => {
+            for diagnostic in &diagnostics {
+                println!("{diagnostic}");
+            }
+        },
+        LintFormat::Json => println!("{}", serde_json::to_string_pretty(&diagnostics)?),
+    }
+
+    let errors = diagnostics
+        .iter()
+        .filter(|v| v.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(anyhow!("found {errors} lint error(s)"));
+    }
+    Ok(())
+}
+
+/// `files`, or every note under the working directory if none were given.
+fn vault_files(files: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+    if files.is_empty() {
+        Ok(glob("**/*.md")?.flatten().collect())
+    } else {
+        Ok(files)
+    }
+}
+
+/// Moves a note and rewrites the links to it in `files`, removing the directory it leaves
+/// empty. With `dry_run`, prints the edits instead.
+pub fn move_note(
+    config: &Config,
+    files: &[PathBuf],
+    old: &Path,
+    new: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    if !old.is_file() {
+        return Err(anyhow!("could not find file `{}`", old.display()));
+    }
+    if new.exists() {
+        return Err(anyhow!("file `{}` already exists", new.display()));
+    }
+
+    // `old` as it's written in `files`, which is what the resolver returns
+    let mut files = files.to_vec();
+    let old = match files
+        .iter()
+        .find(|v| links::normalize(v) == links::normalize(old))
+    {
+        Some(v) => v.clone(),
+        None => {
+            files.push(old.to_path_buf());
+            old.to_path_buf()
+        },
+    };
+    let resolver = Resolver::new(&files);
+    let rename = Rename {
+        old: &old,
+        new,
+        resolver: &resolver,
+    };
+
+    if dry_run {
+        println!("{} -> {}", old.display(), new.display());
+    }
+    let mut changes: Vec<(&Path, String, String)> = vec![];
+    for file in &files {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("could not read file `{}`", file.display()))?;
+        let mut node = to_mdast_from_str(&content)
+            .with_context(|| format!("could not parse file `{}`", file.display()))?;
+
+        let edits = rename.rewrite(file, &mut node);
+        if edits.is_empty() {
+            continue;
+        }
+        if dry_run {
+            for Edit { from, to } in edits {
+                println!("{}: {from} -> {to}", file.display());
+            }
+            continue;
+        }
+
+        let printed = print_node(&content, &node, &config.format)
+            .with_context(|| format!("could not stringify file `{}`", file.display()))?;
+        changes.push((file, content, printed));
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    if let Some(dir) = new.parent().filter(|v| !v.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("could not create directory `{}`", dir.display()))?;
+    }
+    fs::rename(&old, new)
+        .with_context(|| format!("could not move `{}` to `{}`", old.display(), new.display()))?;
+
+    // The moved note's own links are written to its new path. If any file can't be written,
+    // the ones written so far and the note are put back.
+    let path = |file: &Path| {
+        if file == old {
+            new.to_path_buf()
+        } else {
+            file.to_path_buf()
+        }
+    };
+    for (i, (file, _, printed)) in changes.iter().enumerate() {
+        if let Err(err) = fs::write(path(file), printed) {
+            for (file, content, _) in &changes[..i] {
+                let _ = fs::write(path(file), content);
+            }
+            let _ = fs::rename(new, &old);
+            return Err(err)
+                .with_context(|| format!("could not rewrite links in `{}`", file.display()));
+        }
+    }
+
+    if let Some(dir) = old.parent().filter(|v| v.file_name().is_some()) {
+        if fs::read_dir(dir).is_ok_and(|mut v| v.next().is_none()) {
+            fs::remove_dir(dir)
+                .with_context(|| format!("could not remove directory `{}`", dir.display()))?;
+        }
+    }
+    Ok(())
 }
 
 fn run_file(config: &Config, file: &PathBuf) -> Result<()> {
@@ -118,7 +601,7 @@ fn run_file(config: &Config, file: &PathBuf) -> Result<()> {
 
     if config.check {
         let err = to_mdast_from_str(&content)
-            .and_then(|node| print_node(&node))
+            .and_then(|node| print_node(&content, &node, &config.format))
             .is_err();
         if err {
             println!("{}", file.display());
@@ -136,20 +619,20 @@ fn run_file(config: &Config, file: &PathBuf) -> Result<()> {
     }
 
     if config.note {
-        let note = NoteParser::parse(&node)?;
+        let note = parse_note(&content, &node, &config.format)?;
         let s = note.debug_print(());
         println!("{s}");
         return Ok(());
     }
 
     if config.json {
-        let note = NoteParser::parse(&node)?;
+        let note = parse_note(&content, &node, &config.format)?;
         let s = serde_json::to_string_pretty(&note)?;
         println!("{s}");
         return Ok(());
     }
 
-    let content = print_node(&node)
+    let content = print_file(config, file, &content, &node)
         .with_context(|| format!("could not stringify file `{}`", file.display()))?;
 
     if config.write {
@@ -162,3 +645,87 @@ fn run_file(config: &Config, file: &PathBuf) -> Result<()> {
 
     Ok(())
 }
+
+/// Formats a note, applying the options that depend on its file.
+fn print_file(config: &Config, file: &Path, content: &str, node: &Node) -> Result<String> {
+    let options = &config.format;
+    let mut note = parse_note(content, node, options)?.normalize(options)?;
+
+    if options.journal.write {
+        fill_journal_date(&mut note, file, &options.journal);
+    }
+    if options.front_matter.timestamps.auto {
+        update_timestamps(config, file, content, node, &mut note)?;
+    }
+
+    note.print(options.clone())
+}
+
+/// Sets a missing `journalDate` from the file's path.
+fn fill_journal_date(note: &mut Note, file: &Path, options: &JournalOptions) {
+    let Some(date) = journal::journal_date(file, &options.patterns) else {
+        return;
+    };
+    if let Some(meta) = note.meta_mut() {
+        meta.journal_date.get_or_insert(date);
+    }
+}
+
+/// Gives a note without front matter a title from its first H1 or file name, and dates from
+/// the file system.
+fn derive_meta(note: &mut Note, file: &Path, options: &TimestampOptions) -> Result<()> {
+    let title = sections(&note.body)
+        .into_iter()
+        .find(|v| v.depth == 1)
+        .map(|v| v.title.clone())
+        .or_else(|| Some(file.file_stem()?.to_str()?.to_string()));
+
+    if let Some(meta) = note.meta_mut() {
+        meta.title = meta.title.take().or(title);
+        derive_dates(meta, file, options)?;
+    }
+    Ok(())
+}
+
+/// Sets `createdAt` and `updatedAt` from the file system.
+fn derive_dates(meta: &mut Meta, file: &Path, options: &TimestampOptions) -> Result<()> {
+    let offset = options.default_offset();
+    meta.created_at = Some(timestamps::created_at(file, CreatedSource::Ctime, offset)?);
+    meta.updated_at = Some(timestamps::modified_at(file, offset)?);
+    Ok(())
+}
+
+/// Fills a missing `createdAt`, and sets `updatedAt` if the file is written and its body
+/// changes beyond formatting.
+fn update_timestamps(
+    config: &Config,
+    file: &Path,
+    content: &str,
+    node: &Node,
+    note: &mut Note,
+) -> Result<()> {
+    let options = &config.format;
+    // Both bodies are printed, so that formatting alone doesn't count as a change
+    let body_changed = config.write && {
+        let old = Note::new(None, parse_note(content, node, options)?.body);
+        let new = Note::new(None, note.body.clone());
+        old.print(options.clone())? != new.print(options.clone())?
+    };
+    let Some(meta) = note.meta_mut() else {
+        return Ok(());
+    };
+
+    let timestamps = &options.front_matter.timestamps;
+    if meta.created_at.is_none() {
+        let offset = timestamps.default_offset();
+        meta.created_at = Some(timestamps::created_at(
+            file,
+            timestamps.created_from,
+            offset,
+        )?);
+    }
+    if body_changed {
+        meta.updated_at = Some(timestamps::now(timestamps.default_offset()));
+    }
+    Ok(())
+}
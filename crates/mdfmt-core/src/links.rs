@@ -0,0 +1,212 @@
+use std::path::{Component, Path, PathBuf};
+
+mod check;
+
+pub use check::{check_links, Problem};
+
+/// Finds the notes that wiki links and markdown links point to.
+pub struct Resolver {
+    notes: Vec<Note>,
+}
+
+struct Note {
+    path: PathBuf,
+    /// Without `.` and `..`.
+    normalized: PathBuf,
+    /// Lowercase, without the extension, for wiki links.
+    name: PathBuf,
+}
+
+impl Resolver {
+    pub fn new<'a>(paths: impl IntoIterator<Item = &'a PathBuf>) -> Self {
+        let mut notes: Vec<Note> = paths
+            .into_iter()
+            .map(|path| {
+                let normalized = normalize(path);
+                Note {
+                    path: path.clone(),
+                    name: PathBuf::from(normalized.to_string_lossy().to_lowercase())
+                        .with_extension(""),
+                    normalized,
+                }
+            })
+            .collect();
+        // Like Obsidian, the shortest path wins when several notes have the same name
+        notes.sort_by_key(|v| (v.normalized.components().count(), v.normalized.clone()));
+        Self { notes }
+    }
+
+    /// `[[name]]` matches a note by file name, `[[dir/name]]` by the end of its path.
+    pub fn wiki_link(&self, target: &str) -> Option<&Path> {
+        let target = target.to_lowercase();
+        let target = target.strip_suffix(".md").unwrap_or(&target);
+        self.notes
+            .iter()
+            .find(|v| v.name.ends_with(target))
+            .map(|v| v.path.as_path())
+    }
+
+    /// Whether `[[target]]` could mean a note other than `except`.
+    pub fn is_taken(&self, target: &str, except: &Path) -> bool {
+        let target = target.to_lowercase();
+        self.notes
+            .iter()
+            .any(|v| v.path != except && v.name.ends_with(&target))
+    }
+
+    /// A markdown link to a file, relative to the note it's in.
+    pub fn path(&self, source: &Path, link: &str) -> Option<&Path> {
+        let path = resolve(source, link);
+        let candidates = [path.clone(), with_md_extension(&path)];
+
+        self.notes
+            .iter()
+            .find(|v| candidates.contains(&v.normalized))
+            .map(|v| v.path.as_path())
+    }
+}
+
+/// Extensions of files that links embed or download rather than open as notes.
+const ATTACHMENTS: &[&str] = &[
+    "avif", "bmp", "gif", "jpeg", "jpg", "png", "svg", "webp", "flac", "m4a", "mp3", "ogg", "wav",
+    "3gp", "mkv", "mov", "mp4", "ogv", "webm", "pdf", "canvas", "csv", "zip",
+];
+
+/// Links to images, PDFs and other files that aren't notes. Only known extensions count, so
+/// `[[v1.2 notes]]` is a note.
+pub fn is_attachment(link: &str) -> bool {
+    Path::new(link)
+        .extension()
+        .and_then(|v| v.to_str())
+        .is_some_and(|ext| ATTACHMENTS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// `path` with `.md` appended, as a note's name may have dots of its own.
+pub fn with_md_extension(path: &Path) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".md");
+    PathBuf::from(path)
+}
+
+/// The path of a link to a file in the vault, without the fragment and percent-encoding.
+pub fn local_path(url: &str) -> Option<String> {
+    if url.is_empty() || url.starts_with('#') || url.contains(':') {
+        return None;
+    }
+    let path = url.split(['#', '?']).next()?;
+    Some(percent_decode(path))
+}
+
+/// Where `link` in `source` points, relative to the working directory.
+pub fn resolve(source: &Path, link: &str) -> PathBuf {
+    let path = match link.strip_prefix('/') {
+        Some(link) => PathBuf::from(link),
+        None => source.parent().unwrap_or(Path::new("")).join(link),
+    };
+    normalize(&path)
+}
+
+/// `to` relative to the directory of `source`, with `/` separators.
+pub fn relative_path(source: &Path, to: &Path) -> String {
+    let dir = normalize(source.parent().unwrap_or(Path::new("")));
+    let to = normalize(to);
+    let common = dir
+        .components()
+        .zip(to.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = vec!["..".to_string(); dir.components().count() - common];
+    parts.extend(
+        to.components()
+            .skip(common)
+            .map(|v| v.as_os_str().to_string_lossy().to_string()),
+    );
+    parts.join("/")
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut res: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .and_then(|v| u8::from_str_radix(v, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(v)) => {
+                res.push(v);
+                i += 3;
+            },
+            (v, _) => {
+                res.push(v);
+                i += 1;
+            },
+        }
+    }
+    String::from_utf8_lossy(&res).to_string()
+}
+
+/// Removes `.` and resolves `..` without touching the file system.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut res = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir if res.file_name().is_some() => {
+                res.pop();
+            },
+            v => res.push(v),
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn resolve_links() {
+        let paths = vec![
+            PathBuf::from("./notes/a.md"),
+            PathBuf::from("notes/sub/a.md"),
+            PathBuf::from("b c.md"),
+        ];
+        let resolver = Resolver::new(&paths);
+
+        assert_eq!(resolver.wiki_link("A"), Some(paths[0].as_path()));
+        assert_eq!(resolver.wiki_link("sub/a.md"), Some(paths[1].as_path()));
+        assert_eq!(resolver.wiki_link("x"), None);
+        assert_eq!(
+            resolver.path(Path::new("notes/sub/a.md"), "../../b%20c.md"),
+            None
+        );
+        assert_eq!(
+            resolver.path(
+                Path::new("notes/sub/a.md"),
+                &local_path("../../b%20c.md#x").unwrap()
+            ),
+            Some(paths[2].as_path())
+        );
+        assert_eq!(
+            resolver.path(Path::new("notes/sub/a.md"), "../a"),
+            Some(paths[0].as_path())
+        );
+    }
+
+    #[test]
+    fn relative() {
+        assert_eq!(
+            relative_path(Path::new("notes/sub/a.md"), Path::new("notes/b.md")),
+            "../b.md"
+        );
+        assert_eq!(
+            relative_path(Path::new("a.md"), Path::new("./x/b.md")),
+            "x/b.md"
+        );
+    }
+}
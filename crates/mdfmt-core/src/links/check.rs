@@ -0,0 +1,333 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use itertools::Itertools;
+use markdown::mdast::Node;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{is_attachment, local_path, percent_decode, resolve, with_md_extension, Resolver};
+use crate::{
+    ast::wiki_link::wiki_link_of,
+    note::{sections, FormatOptions, NoteParser, SlugStyle, WikiLink},
+    split_json_front_matter, to_mdast_from_str,
+};
+
+/// Text that may be a reference to a footnote without a definition.
+static FOOTNOTE_LABEL: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[\^([^\]\s]+)\]").unwrap());
+
+#[derive(PartialEq, Debug)]
+pub struct Problem {
+    pub file: PathBuf,
+    pub line: usize,
+    pub message: String,
+}
+
+/// Finds links in `files` to missing notes, headings, blocks and footnotes.
+pub fn check_links(files: &[PathBuf], options: &FormatOptions) -> Result<Vec<Problem>> {
+    let mut checker = Checker {
+        resolver: Resolver::new(files),
+        options,
+        targets: HashMap::new(),
+        problems: vec![],
+    };
+    for file in files {
+        checker.check_file(file)?;
+    }
+    Ok(checker.problems)
+}
+
+struct Checker<'a> {
+    resolver: Resolver,
+    options: &'a FormatOptions,
+    /// Anchors of the notes links point to, `None` for those that can't be read.
+    targets: HashMap<PathBuf, Option<Anchors>>,
+    problems: Vec<Problem>,
+}
+
+/// Where a fragment can point in a note.
+struct Anchors {
+    headings: BTreeSet<String>,
+    blocks: BTreeSet<String>,
+}
+
+/// A link within a note being checked.
+struct Source<'a> {
+    file: &'a Path,
+    /// Lines before the mdast, i.e. JSON front matter.
+    line_offset: usize,
+    anchors: &'a Anchors,
+    footnotes: &'a BTreeSet<String>,
+}
+
+impl Checker<'_> {
+    fn check_file(&mut self, file: &Path) -> Result<()> {
+        let content = fs::read_to_string(file)
+            .with_context(|| format!("could not read file `{}`", file.display()))?;
+        let node = to_mdast_from_str(&content)
+            .with_context(|| format!("could not parse file `{}`", file.display()))?;
+
+        let (_, body) = split_json_front_matter(&content);
+        let line_offset = content[..content.len() - body.len()].matches('\n').count();
+        let anchors = self.anchors_of(&node)?;
+        let mut footnotes = BTreeSet::new();
+        collect_footnotes(&node, &mut footnotes);
+        let node = with_missing_footnotes(&content, &footnotes)
+            .with_context(|| format!("could not parse file `{}`", file.display()))?
+            .unwrap_or(node);
+
+        let source = Source {
+            file,
+            line_offset,
+            anchors: &anchors,
+            footnotes: &footnotes,
+        };
+        self.visit(&node, &source);
+        Ok(())
+    }
+
+    fn visit(&mut self, node: &Node, source: &Source) {
+        let line = node
+            .position()
+            .map(|v| v.start.line + source.line_offset)
+            .unwrap_or_default();
+
+        match node {
+            Node::Text(v) => {
+                if let Some(link) = wiki_link_of(node) {
+                    self.check_wiki_link(&link, &v.value, line, source);
+                }
+            },
+            Node::FootnoteReference(v)
+                if !source.footnotes.contains(&v.identifier.to_lowercase()) =>
+            {
+                let message = format!("missing footnote definition `[^{}]`", v.identifier);
+                self.report(source, line, message);
+            },
+            Node::Link(v) => self.check_url(&v.url, line, source),
+            Node::Definition(v) => self.check_url(&v.url, line, source),
+            _ => {},
+        }
+
+        for child in node.children().into_iter().flatten() {
+            self.visit(child, source);
+        }
+    }
+
+    fn check_wiki_link(&mut self, link: &WikiLink, raw: &str, line: usize, source: &Source) {
+        let fragment = match (&link.heading, &link.block) {
+            // `[[note#A#B]]` links to `B` under `A`
+            (Some(heading), _) => heading.rsplit('#').next().map(|v| v.to_string()),
+            (_, Some(block)) => Some(format!("^{block}")),
+            _ => None,
+        };
+
+        if link.target.is_empty() {
+            self.check_fragment(source.anchors, fragment, raw, "this note", line, source);
+            return;
+        }
+        let Some(target) = self.resolver.wiki_link(&link.target).map(Path::to_path_buf) else {
+            if !is_attachment(&link.target) {
+                self.report(source, line, format!("broken link `{raw}`"));
+            }
+            return;
+        };
+        self.check_target(&target, fragment, raw, line, source);
+    }
+
+    fn check_url(&mut self, url: &str, line: usize, source: &Source) {
+        let Some(path) = local_path(url).or_else(|| url.starts_with('#').then(String::new)) else {
+            return;
+        };
+        let fragment = url
+            .split_once('#')
+            .map(|(_, v)| percent_decode(v))
+            .filter(|v| !v.is_empty());
+
+        if path.is_empty() {
+            self.check_fragment(source.anchors, fragment, url, "this note", line, source);
+            return;
+        }
+        let resolved = resolve(source.file, &path);
+        let target = [resolved.clone(), with_md_extension(&resolved)]
+            .into_iter()
+            .find(|v| v.is_file());
+        let Some(target) = target else {
+            self.report(source, line, format!("broken link `{url}`"));
+            return;
+        };
+        if target.extension().is_some_and(|v| v == "md") {
+            self.check_target(&target, fragment, url, line, source);
+        }
+    }
+
+    fn check_target(
+        &mut self,
+        target: &Path,
+        fragment: Option<String>,
+        link: &str,
+        line: usize,
+        source: &Source,
+    ) {
+        if fragment.is_none() {
+            return;
+        }
+        if !self.targets.contains_key(target) {
+            let anchors = fs::read_to_string(target).ok().and_then(|content| {
+                let node = to_mdast_from_str(&content).ok()?;
+                self.anchors_of(&node).ok()
+            });
+            self.targets.insert(target.to_path_buf(), anchors);
+        }
+
+        let Some(Some(anchors)) = self.targets.remove(target) else {
+            return;
+        };
+        let name = format!("`{}`", target.display());
+        self.check_fragment(&anchors, fragment, link, &name, line, source);
+        self.targets.insert(target.to_path_buf(), Some(anchors));
+    }
+
+    fn check_fragment(
+        &mut self,
+        anchors: &Anchors,
+        fragment: Option<String>,
+        link: &str,
+        note: &str,
+        line: usize,
+        source: &Source,
+    ) {
+        let Some(fragment) = fragment else {
+            return;
+        };
+        match fragment.strip_prefix('^') {
+            Some(block) if !anchors.blocks.contains(block) => {
+                let message = format!("missing block `^{block}` in {note} (`{link}`)");
+                self.report(source, line, message);
+            },
+            None if !anchors.headings.contains(&fragment.to_lowercase()) => {
+                let message = format!("missing heading `#{fragment}` in {note} (`{link}`)");
+                self.report(source, line, message);
+            },
+            _ => {},
+        }
+    }
+
+    /// Headings match by slug in any style, or by title.
+    fn anchors_of(&self, node: &Node) -> Result<Anchors> {
+        let note = NoteParser::parse_with(node, self.options)?;
+        let mut headings = BTreeSet::new();
+        for section in sections(&note.body) {
+            headings.insert(section.slug.to_lowercase());
+            headings.insert(section.title.to_lowercase());
+            headings.insert(SlugStyle::Github.slugify(&section.title));
+            headings.insert(SlugStyle::Obsidian.slugify(&section.title).to_lowercase());
+        }
+
+        let mut blocks = BTreeSet::new();
+        collect_blocks(node, &mut blocks);
+
+        Ok(Anchors { headings, blocks })
+    }
+
+    fn report(&mut self, source: &Source, line: usize, message: String) {
+        self.problems.push(Problem {
+            file: source.file.to_path_buf(),
+            line,
+            message,
+        });
+    }
+}
+
+/// Block IDs, `text ^id` at the end of a paragraph or `^id` as one of its own. List items
+/// have theirs in their paragraphs.
+fn collect_blocks(node: &Node, res: &mut BTreeSet<String>) {
+    if let Node::Paragraph(v) = node {
+        if let [.., Node::Text(text)] = v.children.as_slice() {
+            let alone = v.children.len() == 1;
+            if let Some((before, id)) = text.value.trim_end().rsplit_once('^') {
+                let valid = (before.ends_with([' ', '\n']) || (alone && before.is_empty()))
+                    && !id.is_empty()
+                    && id.chars().all(|c| c.is_alphanumeric() || c == '-');
+                if valid {
+                    res.insert(id.to_string());
+                }
+            }
+        }
+    }
+    for child in node.children().into_iter().flatten() {
+        collect_blocks(child, res);
+    }
+}
+
+/// `content` parsed again with a placeholder definition for each footnote label that isn't
+/// defined, as `markdown` leaves references to those as text. `None` if there are none.
+fn with_missing_footnotes(content: &str, defined: &BTreeSet<String>) -> Result<Option<Node>> {
+    let missing = FOOTNOTE_LABEL
+        .captures_iter(content)
+        .map(|v| v[1].to_string())
+        .filter(|v| !defined.contains(&v.to_lowercase()))
+        .unique()
+        .collect::<Vec<String>>();
+    if missing.is_empty() {
+        return Ok(None);
+    }
+
+    let definitions = missing
+        .iter()
+        .map(|v| format!("[^{v}]: missing\n"))
+        .collect::<String>();
+    let mut node = to_mdast_from_str(&format!("{content}\n\n{definitions}"))?;
+    if let Some(children) = node.children_mut() {
+        children.retain(|v| match v {
+            Node::FootnoteDefinition(v) => defined.contains(&v.identifier.to_lowercase()),
+            _ => true,
+        });
+    }
+    Ok(Some(node))
+}
+
+fn collect_footnotes(node: &Node, res: &mut BTreeSet<String>) {
+    if let Node::FootnoteDefinition(v) = node {
+        res.insert(v.identifier.to_lowercase());
+    }
+    for child in node.children().into_iter().flatten() {
+        collect_footnotes(child, res);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn find_problems() -> Result<()> {
+        let files = vec![
+            PathBuf::from("fixtures/links/a.md"),
+            PathBuf::from("fixtures/links/sub/b.md"),
+        ];
+        let problems = check_links(&files, &FormatOptions::default())?
+            .into_iter()
+            .map(|v| format!("{}:{}: {}", v.file.display(), v.line, v.message))
+            .collect::<Vec<String>>();
+
+        assert_eq!(problems, vec![
+            "fixtures/links/a.md:6: missing heading `#Nope` in `fixtures/links/sub/b.md` (`[[b#Nope]]`)",
+            "fixtures/links/a.md:6: broken link `[[missing]]`",
+            "fixtures/links/a.md:6: missing block `^zz` in `fixtures/links/sub/b.md` (`[[b#^zz]]`)",
+            "fixtures/links/a.md:8: missing heading `#zzz` in `fixtures/links/sub/b.md` (`sub/b.md#zzz`)",
+            "fixtures/links/a.md:8: broken link `nope.md`",
+            "fixtures/links/a.md:8: missing heading `#bad` in this note (`#bad`)",
+            "fixtures/links/a.md:10: missing footnote definition `[^2]`",
+            "fixtures/links/a.md:14: missing block `^code` in `fixtures/links/sub/b.md` (`[[b#^code]]`)",
+            "fixtures/links/sub/b.md:5: missing heading `#Missing` in this note (`[[#Missing]]`)",
+        ]);
+        Ok(())
+    }
+}
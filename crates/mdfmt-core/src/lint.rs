@@ -0,0 +1,306 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{anyhow, Result};
+use markdown::mdast::Node;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    note::{FormatOptions, Note},
+    split_json_front_matter,
+};
+
+mod rules;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Disables a rule
+    Off,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "off" => Ok(Self::Off),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            _ => Err(anyhow!("unknown severity `{s}`")),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub enum LintFormat {
+    /// `file:line: severity[rule]: message`
+    #[default]
+    Text,
+    Json,
+}
+
+impl std::fmt::Display for LintFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl std::str::FromStr for LintFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow!("unknown lint format `{s}`")),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct LintOptions {
+    /// Severity of each rule by ID, e.g. `bare-url = "off"`. Rules default to `warning`.
+    pub rules: BTreeMap<String, Severity>,
+}
+
+/// What a rule checks a note with.
+pub struct LintInput<'a> {
+    pub note: &'a Note,
+    pub node: &'a Node,
+    pub content: &'a str,
+    pub options: &'a FormatOptions,
+    /// Lines of JSON front matter, which isn't part of `node`.
+    line_offset: usize,
+}
+
+impl LintInput<'_> {
+    /// Line of `node` in the file.
+    pub fn line(&self, node: &Node) -> usize {
+        node.position()
+            .map(|v| v.start.line + self.line_offset)
+            .unwrap_or(1)
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Finding {
+    pub line: usize,
+    pub message: String,
+}
+
+pub trait Rule {
+    /// Kebab-case name used in config and output.
+    fn id(&self) -> &'static str;
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, input: &LintInput) -> Vec<Finding>;
+}
+
+#[derive(PartialEq, Debug, Serialize)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}[{}]: {}",
+            self.file, self.line, self.severity, self.rule, self.message
+        )
+    }
+}
+
+/// Runs the enabled rules on notes.
+pub struct Linter {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+    options: LintOptions,
+}
+
+impl Linter {
+    /// The built-in rules, configured by `options`.
+    pub fn new(options: &LintOptions) -> Result<Self> {
+        let mut linter = Self {
+            rules: vec![],
+            options: options.clone(),
+        };
+        for rule in rules::builtin() {
+            linter = linter.with_rule(rule);
+        }
+
+        if let Some(id) = options
+            .rules
+            .keys()
+            .find(|id| !linter.rules.iter().any(|(v, _)| v.id() == id.as_str()))
+        {
+            return Err(anyhow!("unknown lint rule `{id}`"));
+        }
+        Ok(linter)
+    }
+
+    pub fn with_rule(mut self, rule: Box<dyn Rule>) -> Self {
+        let severity = self
+            .options
+            .rules
+            .get(rule.id())
+            .copied()
+            .unwrap_or_else(|| rule.default_severity());
+        self.rules.push((rule, severity));
+        self
+    }
+
+    pub fn lint(
+        &self,
+        file: &Path,
+        content: &str,
+        node: &Node,
+        note: &Note,
+        options: &FormatOptions,
+    ) -> Vec<Diagnostic> {
+        let (_, body) = split_json_front_matter(content);
+        let input = LintInput {
+            note,
+            node,
+            content,
+            options,
+            line_offset: content[..content.len() - body.len()].matches('\n').count(),
+        };
+
+        let mut res: Vec<Diagnostic> = self
+            .rules
+            .iter()
+            .filter(|(_, severity)| *severity != Severity::Off)
+            .flat_map(|(rule, severity)| {
+                rule.check(&input)
+                    .into_iter()
+                    .map(|Finding { line, message }| Diagnostic {
+                        file: file.display().to_string(),
+                        line,
+                        rule: rule.id(),
+                        severity: *severity,
+                        message,
+                    })
+            })
+            .collect();
+        res.sort_by_key(|v| v.line);
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{note::NoteParser, to_mdast_from_str};
+
+    fn lint(options: &LintOptions, content: &str) -> Result<Vec<Diagnostic>> {
+        let node = to_mdast_from_str(content)?;
+        let note = NoteParser::parse(&node)?;
+        let linter = Linter::new(options)?;
+        Ok(linter.lint(
+            Path::new("a.md"),
+            content,
+            &node,
+            &note,
+            &FormatOptions::default(),
+        ))
+    }
+
+    #[test]
+    fn configure_rules() -> Result<()> {
+        let options: LintOptions = toml::from_str(
+            r#"
+            [rules]
+            missing-title = "off"
+            bare-url = "error"
+            "#,
+        )?;
+        assert_eq!(
+            lint(&options, "{}\nhttps://a.com\n")?,
+            vec![Diagnostic {
+                file: "a.md".into(),
+                line: 2,
+                rule: "bare-url",
+                severity: Severity::Error,
+                message: "bare URL `https://a.com`".into(),
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_rule() {
+        let options = LintOptions {
+            rules: BTreeMap::from([("foo".to_string(), Severity::Off)]),
+        };
+        assert!(Linter::new(&options).is_err());
+    }
+
+    struct NoTodo;
+
+    impl Rule for NoTodo {
+        fn id(&self) -> &'static str {
+            "no-todo"
+        }
+
+        fn check(&self, input: &LintInput) -> Vec<Finding> {
+            input
+                .content
+                .lines()
+                .enumerate()
+                .filter(|(_, v)| v.contains("TODO"))
+                .map(|(i, _)| Finding {
+                    line: i + 1,
+                    message: "TODO left in note".into(),
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn custom_rule() -> Result<()> {
+        let content = "# Foo\n\nTODO\n";
+        let node = to_mdast_from_str(content)?;
+        let note = NoteParser::parse(&node)?;
+        let linter = Linter::new(&LintOptions::default())?.with_rule(Box::new(NoTodo));
+        let diagnostics = linter.lint(
+            Path::new("a.md"),
+            content,
+            &node,
+            &note,
+            &FormatOptions::default(),
+        );
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>(),
+            vec!["a.md:3: warning[no-todo]: TODO left in note"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,329 @@
+use markdown::mdast::{Heading, Node};
+
+use super::{Finding, LintInput, Rule};
+use crate::note::{find_duplicate_slugs, Metadata, NoteParser};
+
+const HEADING_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '。', '，', '；', '：', '！'];
+
+pub fn builtin() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(HeadingIncrement),
+        Box::new(EmptySection),
+        Box::new(DuplicateHeading),
+        Box::new(MissingTitle),
+        Box::new(HeadingPunctuation),
+        Box::new(BareUrl),
+        Box::new(EmptyCard),
+    ]
+}
+
+/// Reports headings more than one level below the previous one, e.g. `###` right under `#`.
+struct HeadingIncrement;
+
+impl Rule for HeadingIncrement {
+    fn id(&self) -> &'static str {
+        "heading-increment"
+    }
+
+    fn check(&self, input: &LintInput) -> Vec<Finding> {
+        let mut res = vec![];
+        let mut previous: Option<u8> = None;
+        for (node, heading) in headings(input.node) {
+            if let Some(previous) = previous.filter(|v| heading.depth > v + 1) {
+                res.push(Finding {
+                    line: input.line(node),
+                    message: format!(
+                        "heading level skipped from h{previous} to h{}",
+                        heading.depth
+                    ),
+                });
+            }
+            previous = Some(heading.depth);
+        }
+        res
+    }
+}
+
+/// Reports headings with neither content nor subsections before the next heading.
+struct EmptySection;
+
+impl Rule for EmptySection {
+    fn id(&self) -> &'static str {
+        "empty-section"
+    }
+
+    fn check(&self, input: &LintInput) -> Vec<Finding> {
+        let children = input.node.children().map(Vec::as_slice).unwrap_or_default();
+        children
+            .iter()
+            .enumerate()
+            .filter_map(|(i, node)| {
+                let Node::Heading(heading) = node else {
+                    return None;
+                };
+                let empty = match children.get(i + 1) {
+                    None => true,
+                    Some(Node::Heading(next)) => next.depth <= heading.depth,
+                    Some(_) => false,
+                };
+                empty.then(|| Finding {
+                    line: input.line(node),
+                    message: format!("empty section `{}`", title(heading)),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Reports headings with the slug of an earlier one, as links to them are ambiguous.
+struct DuplicateHeading;
+
+impl Rule for DuplicateHeading {
+    fn id(&self) -> &'static str {
+        "duplicate-heading"
+    }
+
+    fn check(&self, input: &LintInput) -> Vec<Finding> {
+        // Sections come from the top-level headings, in the same order
+        let lines = headings(input.node)
+            .map(|(node, _)| input.line(node))
+            .collect::<Vec<usize>>();
+        let mut res = vec![];
+        for (slug, indices) in find_duplicate_slugs(&input.note.body, &input.options.slug_style) {
+            let lines = indices
+                .iter()
+                .filter_map(|i| lines.get(*i).copied())
+                .collect::<Vec<usize>>();
+            let Some((first, rest)) = lines.split_first() else {
+                continue;
+            };
+            res.extend(rest.iter().map(|line| Finding {
+                line: *line,
+                message: format!("duplicate heading slug `{slug}` (first on line {first})"),
+            }));
+        }
+        res
+    }
+}
+
+/// Reports notes with neither a `title` in the front matter nor an H1.
+struct MissingTitle;
+
+impl Rule for MissingTitle {
+    fn id(&self) -> &'static str {
+        "missing-title"
+    }
+
+    fn check(&self, input: &LintInput) -> Vec<Finding> {
+        let has_title =
+            matches!(&input.note.metadata, Some(Metadata::Meta(meta)) if meta.title.is_some());
+        if has_title || headings(input.node).any(|(_, v)| v.depth == 1) {
+            return vec![];
+        }
+        vec![Finding {
+            line: 1,
+            message: "no `title` in front matter and no H1".into(),
+        }]
+    }
+}
+
+/// Reports headings ending with `.`, `,`, `;`, `:` or `!`. `?` is fine.
+struct HeadingPunctuation;
+
+impl Rule for HeadingPunctuation {
+    fn id(&self) -> &'static str {
+        "heading-punctuation"
+    }
+
+    fn check(&self, input: &LintInput) -> Vec<Finding> {
+        headings(input.node)
+            .filter_map(|(node, heading)| {
+                let title = title(heading);
+                let c = title
+                    .chars()
+                    .last()
+                    .filter(|v| HEADING_PUNCTUATION.contains(v))?;
+                Some(Finding {
+                    line: input.line(node),
+                    message: format!("heading ends with `{c}`: `{title}`"),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Reports URLs written as text rather than `<url>` or `[text](url)`.
+struct BareUrl;
+
+impl Rule for BareUrl {
+    fn id(&self) -> &'static str {
+        "bare-url"
+    }
+
+    fn check(&self, input: &LintInput) -> Vec<Finding> {
+        let mut res = vec![];
+        visit(input.node, &mut |node| {
+            let Node::Link(link) = node else {
+                return;
+            };
+            let text = link
+                .children
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<String>();
+            if text != link.url && format!("mailto:{text}") != link.url {
+                return;
+            }
+            // `<url>` links start at their `<`
+            let line = input.line(node);
+            let source = input.content.lines().nth(line - 1).unwrap_or_default();
+            let column = node.position().map_or(0, |v| v.start.column - 1);
+            if !source.get(column..).is_some_and(|v| v.starts_with('<')) {
+                res.push(Finding {
+                    line,
+                    message: format!("bare URL `{text}`"),
+                });
+            }
+        });
+        res
+    }
+}
+
+/// Reports cards such as `> [!note]` with nothing but a title.
+struct EmptyCard;
+
+impl Rule for EmptyCard {
+    fn id(&self) -> &'static str {
+        "empty-card"
+    }
+
+    fn check(&self, input: &LintInput) -> Vec<Finding> {
+        let parser = NoteParser::new(input.options);
+        let mut res = vec![];
+        visit(input.node, &mut |node| {
+            let Node::BlockQuote(block_quote) = node else {
+                return;
+            };
+            if let Some(kind) = parser.empty_card(block_quote) {
+                res.push(Finding {
+                    line: input.line(node),
+                    message: format!("empty `{kind}` card"),
+                });
+            }
+        });
+        res
+    }
+}
+
+/// Top-level headings with their nodes, for lines.
+fn headings(root: &Node) -> impl Iterator<Item = (&Node, &Heading)> {
+    root.children()
+        .into_iter()
+        .flatten()
+        .filter_map(|node| match node {
+            Node::Heading(heading) => Some((node, heading)),
+            _ => None,
+        })
+}
+
+/// Heading text without markdown syntax, as in `Section::title`.
+fn title(heading: &Heading) -> String {
+    heading
+        .children
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn visit<'a>(node: &'a Node, f: &mut impl FnMut(&'a Node)) {
+    f(node);
+    for child in node.children().into_iter().flatten() {
+        visit(child, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::{
+        lint::{LintOptions, Linter},
+        note::FormatOptions,
+        to_mdast_from_str,
+    };
+
+    fn lint(content: &str) -> Vec<String> {
+        let options = FormatOptions::default();
+        let node = to_mdast_from_str(content).unwrap();
+        let note = NoteParser::parse(&node).unwrap();
+        Linter::new(&LintOptions::default())
+            .unwrap()
+            .lint(Path::new("a.md"), content, &node, &note, &options)
+            .iter()
+            .map(|v| format!("{}: {}: {}", v.line, v.rule, v.message))
+            .collect()
+    }
+
+    #[test]
+    fn headings() {
+        assert_eq!(
+            lint(indoc! {"
+                # Foo
+
+                ### Bar:
+
+                text
+
+                ## Bar
+
+                text
+
+                ## Why?
+
+                ## Empty
+            "}),
+            vec![
+                "3: heading-increment: heading level skipped from h1 to h3",
+                "3: heading-punctuation: heading ends with `:`: `Bar:`",
+                "7: duplicate-heading: duplicate heading slug `bar` (first on line 3)",
+                "11: empty-section: empty section `Why?`",
+                "13: empty-section: empty section `Empty`",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_title() {
+        assert_eq!(lint("---\ntitle: Foo\n---\ntext\n"), Vec::<String>::new());
+        assert_eq!(
+            lint("text\n"),
+            vec!["1: missing-title: no `title` in front matter and no H1"]
+        );
+    }
+
+    #[test]
+    fn bare_urls() {
+        assert_eq!(
+            lint("# Foo\n\nhttps://a.com, <https://b.com> and [c](https://c.com)\n"),
+            vec!["3: bare-url: bare URL `https://a.com`"]
+        );
+        assert_eq!(
+            lint("# Foo\n\n<https://a.com> and https://a.com\n"),
+            vec!["3: bare-url: bare URL `https://a.com`"]
+        );
+    }
+
+    #[test]
+    fn empty_cards() {
+        assert_eq!(
+            lint("# Foo\n\n> [!note] Title\n\n> [!todo]\n> body\n\n> [!toc]\n"),
+            vec!["3: empty-card: empty `note` card"]
+        );
+    }
+}
@@ -4,9 +4,14 @@ mod card;
 mod metadata;
 mod note_data;
 mod note_kind;
+mod options;
 mod parser;
 mod section;
+mod slug;
+mod status;
 mod toc;
+mod wiki_link;
+mod yaml_source;
 
 pub use {
     block::*,
@@ -14,33 +19,47 @@ pub use {
     metadata::*,
     note_data::*,
     note_kind::*,
+    options::*,
     parser::NoteParser,
     section::*,
-    toc::{FlattenNode, Toc},
+    slug::{find_duplicate_slugs, sections, SlugStyle},
+    status::StatusWorkflow,
+    toc::{FlattenNode, ListStyle, Toc},
+    wiki_link::{WikiLink, WIKI_LINK},
 };
 
 mod visitor {
     use anyhow::Result;
 
+    use super::options::FormatOptions;
     use crate::chunk::{Chunk, ChunkPrinter};
 
-    pub struct VisitorContext {
+    pub struct VisitorContext<'a> {
         chunks: ChunkPrinter,
         depth: u8,
+        options: &'a FormatOptions,
     }
 
-    impl VisitorContext {
-        pub fn new(depth: u8) -> Self {
+    impl<'a> VisitorContext<'a> {
+        pub fn new(depth: u8, options: &'a FormatOptions) -> Self {
             let chunks = ChunkPrinter::new();
-            Self { chunks, depth }
+            Self {
+                chunks,
+                depth,
+                options,
+            }
         }
 
         pub fn get_depth(&self) -> u8 {
             self.depth
         }
 
+        pub fn get_options(&self) -> &'a FormatOptions {
+            self.options
+        }
+
         pub fn sub(&mut self) -> Self {
-            Self::new(self.depth)
+            Self::new(self.depth, self.options)
         }
 
         pub fn push(&mut self, chunk: Chunk) {
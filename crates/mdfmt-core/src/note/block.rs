@@ -6,8 +6,9 @@ use super::{
     builder::*,
     card::Card,
     note_kind::NoteKind,
+    options::FormatOptions,
     section::Section,
-    toc::FlattenNode,
+    toc::{FlattenNode, ListStyle},
     visitor::{Visitor, VisitorContext},
 };
 use crate::{chunk::Chunk, debug_printer::DebugPrinter, printer::Printer};
@@ -32,11 +33,8 @@ impl Block {
         Self::AnonymousSection(children)
     }
 
-    pub fn section(title: &str, children: Vec<Block>) -> Self {
-        Self::Section(Section {
-            title: title.to_string(),
-            children,
-        })
+    pub fn section(depth: u8, title: &str, children: Vec<Block>) -> Self {
+        Self::Section(Section::new(depth, title, children))
     }
 
     pub fn toc(children: Vec<FlattenNode>) -> Self {
@@ -72,10 +70,11 @@ impl Visitor for Block {
                 Ok(())
             }),
 
-            Block::Section(Section { title, children }) => {
-                context.push(Chunk::Single(heading(context.get_depth(), title)));
+            Block::Section(section) => {
+                let depth = section.level(context.get_depth(), context.get_options().heading_level);
+                context.push(Chunk::Single(heading(depth, section.printed_title())));
                 context.dive(|c| {
-                    for child in children {
+                    for child in &section.children {
                         child.visit(c)?;
                     }
                     Ok(())
@@ -119,14 +118,22 @@ impl Visitor for Block {
             },
 
             Block::Toc(nodes) => {
+                let mut counters: Vec<usize> = vec![];
                 let s = nodes
                     .iter()
-                    .map(|FlattenNode(indent, value)| {
-                        format!("> {}- {}", INDENT.repeat(indent - 1), value)
+                    .map(|FlattenNode(indent, value, style)| {
+                        counters.resize(*indent, 0);
+                        counters[indent - 1] += 1;
+                        let marker = match style {
+                            ListStyle::Bullet => "-".to_string(),
+                            ListStyle::Ordered => format!("{}.", counters[indent - 1]),
+                        };
+                        format!("> {}{marker} {}", INDENT.repeat(indent - 1), value)
                     })
-                    .collect::<Vec<String>>()
-                    .join("\n");
-                context.push(Chunk::Double(format!("> [!toc]\n{s}")));
+                    .collect::<Vec<String>>();
+                context.push(Chunk::Double(
+                    [vec!["> [!toc]".to_string()], s].concat().join("\n"),
+                ));
                 Ok(())
             },
         }
@@ -141,7 +148,8 @@ impl Printer for Block {
     type Options = BlockPrinterOptions;
 
     fn print(&self, options: Self::Options) -> Result<String> {
-        let context = &mut VisitorContext::new(options.depth);
+        let format_options = FormatOptions::default();
+        let context = &mut VisitorContext::new(options.depth, &format_options);
 
         self.visit(context)?;
 
@@ -163,7 +171,7 @@ impl DebugPrinter for Block {
 
             Block::Section(x) => {
                 let children = children_to_string(&x.children, depth);
-                format!("{}[Section] {}\n{children}\n", indent(depth), x.title)
+                format!("{}[Section] {}\n{children}\n", indent(depth), x.raw_title)
             },
 
             Block::Card(x) => format!(
@@ -177,7 +185,7 @@ impl DebugPrinter for Block {
                 "{}[toc]\n{}",
                 depth,
                 &x.iter()
-                    .map(|FlattenNode(i, text)| line(depth + i, text))
+                    .map(|FlattenNode(i, text, _)| line(depth + i, text))
                     .collect::<Vec<String>>()
                     .join("\n"),
             ),
@@ -1,27 +1,44 @@
 use std::collections::BTreeMap;
 
 use anyhow::{Context, Result};
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, SecondsFormat};
 use serde::{Deserialize, Serialize};
 use serde_with::{formats::PreferMany, serde_as, skip_serializing_none, DisplayFromStr, OneOrMany};
 use tsify::Tsify;
 use yaml_rust::{YamlEmitter, YamlLoader};
 
 use super::{
+    options::{FrontMatterFormat, FrontMatterOptions},
     toc::Toc,
     visitor::{Visitor, VisitorContext},
+    yaml_source::YamlSource,
 };
 use crate::{
     chunk::Chunk,
-    date::{FlexibleDate, FlexibleDateTime},
+    date::{
+        parse_date, parse_date_time, parse_timestamp, DateOptions, FlexibleDate, FlexibleDateTime,
+    },
     note::NoteKind,
 };
 
+/// `toc` value asking for a TOC generated from the note's own headings.
+pub const TOC_AUTO: &str = "auto";
+
+/// Keys holding a date and time, read with the configured `DateOptions`.
+const DATE_TIME_KEYS: &[&str] = &["createdAt", "updatedAt"];
+
+/// Keys holding a date.
+const DATE_KEYS: &[&str] = &["journalDate"];
+
+#[allow(clippy::large_enum_variant)]
 #[derive(PartialEq, Serialize, Deserialize, Debug, Tsify)]
 #[serde(tag = "type", content = "value")]
 pub enum Metadata {
     Meta(Meta),
     Raw(String),
+    RawToml(String),
+    /// JSON front matter that couldn't be read, with its delimiters.
+    RawJson(String),
 }
 
 impl std::str::FromStr for Metadata {
@@ -33,10 +50,20 @@ impl std::str::FromStr for Metadata {
 }
 
 impl Metadata {
-    pub fn to_md(&self) -> Result<String> {
+    /// Prints the front matter with its delimiters.
+    pub fn to_md(&self, options: &FrontMatterOptions) -> Result<String> {
         match self {
-            Self::Meta(v) => v.to_md(),
-            Self::Raw(v) => Ok(v.to_owned() + "\n"),
+            Self::Meta(v) => match options.format.unwrap_or(v.format) {
+                FrontMatterFormat::Yaml => Ok(format!("---\n{}---", v.to_md(options)?)),
+                FrontMatterFormat::Toml => Ok(format!("+++\n{}+++", v.to_toml(options)?)),
+                FrontMatterFormat::Json if v.json_delimited => {
+                    Ok(format!(";;;\n{}\n;;;", v.to_json(options)?))
+                },
+                FrontMatterFormat::Json => v.to_json(options),
+            },
+            Self::Raw(v) => Ok(format!("---\n{v}\n---")),
+            Self::RawToml(v) => Ok(format!("+++\n{v}\n+++")),
+            Self::RawJson(v) => Ok(v.clone()),
         }
     }
 
@@ -50,7 +77,9 @@ impl Metadata {
 
 impl Visitor for Metadata {
     fn visit(&self, context: &mut VisitorContext) -> Result<()> {
-        context.push(Chunk::Single(format!("---\n{}---", self.to_md()?)));
+        context.push(Chunk::Single(
+            self.to_md(&context.get_options().front_matter)?,
+        ));
         Ok(())
     }
 }
@@ -77,10 +106,10 @@ pub struct Meta {
     pub journal_date: Option<NaiveDate>,
 
     #[serde_as(as = "Option<FlexibleDateTime>")]
-    pub created_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<FixedOffset>>,
 
     #[serde_as(as = "Option<FlexibleDateTime>")]
-    pub updated_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<FixedOffset>>,
 
     #[serde_as(as = "Option<OneOrMany<_, PreferMany>>")]
     pub author: Option<Vec<String>>,
@@ -91,6 +120,18 @@ pub struct Meta {
     #[serde(flatten)]
     #[serde_as(as = "BTreeMap<DisplayFromStr, _>")]
     pub others: BTreeMap<String, serde_yaml::Value>,
+
+    /// The front matter as written, if this was parsed from a note.
+    #[serde(skip)]
+    pub source: Option<String>,
+
+    /// The format the front matter was written in.
+    #[serde(skip)]
+    pub format: FrontMatterFormat,
+
+    /// Whether JSON front matter was written between `;;;` lines.
+    #[serde(skip)]
+    pub json_delimited: bool,
 }
 
 #[serde_as]
@@ -109,10 +150,10 @@ pub struct Bookmark {
     journal_date: Option<NaiveDate>,
 
     #[serde_as(as = "Option<FlexibleDateTime>")]
-    created_at: Option<DateTime<Utc>>,
+    created_at: Option<DateTime<FixedOffset>>,
 
     #[serde_as(as = "Option<FlexibleDateTime>")]
-    updated_at: Option<DateTime<Utc>>,
+    updated_at: Option<DateTime<FixedOffset>>,
 
     url: Option<String>,
 
@@ -128,18 +169,264 @@ impl std::str::FromStr for Meta {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        serde_yaml::from_str(s).with_context(|| "could not stringify front matter".to_string())
+        Self::from_yaml(s, &DateOptions::default())
+    }
+}
+
+/// Rewrites the dates in front matter, including those of `bookmark`, as RFC 3339 so that they
+/// deserialize the same whatever `options` they were read with.
+fn resolve_dates(value: &mut serde_yaml::Value, options: &DateOptions) {
+    let Some(map) = value.as_mapping_mut() else {
+        return;
+    };
+    for (key, value) in map.iter_mut() {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        if key == "bookmark" {
+            resolve_dates(value, options);
+        } else if DATE_TIME_KEYS.contains(&key) {
+            if let Some(t) = date_time_of(value, options) {
+                *value = t.to_rfc3339_opts(SecondsFormat::AutoSi, true).into();
+            }
+        } else if DATE_KEYS.contains(&key) {
+            if let Some(t) = date_of(value, options) {
+                *value = t.to_string().into();
+            }
+        }
+    }
+}
+
+fn date_time_of(value: &serde_yaml::Value, options: &DateOptions) -> Option<DateTime<FixedOffset>> {
+    match value {
+        serde_yaml::Value::String(v) => parse_date_time(v, options),
+        serde_yaml::Value::Number(v) => parse_timestamp(v.as_i64()?, options.offset),
+        _ => None,
+    }
+}
+
+fn date_of(value: &serde_yaml::Value, options: &DateOptions) -> Option<NaiveDate> {
+    match value {
+        serde_yaml::Value::String(v) => parse_date(v, options),
+        serde_yaml::Value::Number(v) => {
+            Some(parse_timestamp(v.as_i64()?, options.offset)?.date_naive())
+        },
+        _ => None,
+    }
+}
+
+/// TOML dates and times have no JSON counterpart, so they become strings.
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(v) => serde_json::Value::String(v),
+        toml::Value::Integer(v) => v.into(),
+        toml::Value::Float(v) => v.into(),
+        toml::Value::Boolean(v) => v.into(),
+        toml::Value::Datetime(v) => v.to_string().into(),
+        toml::Value::Array(v) => v.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(v) => v.into_iter().map(|(k, v)| (k, toml_to_json(v))).collect(),
     }
 }
 
 impl Meta {
-    pub fn to_md(&self) -> Result<String> {
-        let s = serde_yaml::to_string(self)
-            .with_context(|| "could not stringify front matter".to_string())?;
-        let s = self.fix_indent(&s);
+    pub fn to_md(&self, options: &FrontMatterOptions) -> Result<String> {
+        let map = self.to_mapping(options)?;
+
+        let source = match self.format {
+            FrontMatterFormat::Yaml => self.source.as_deref().and_then(YamlSource::parse),
+            _ => None,
+        };
+        let Some(source) = source else {
+            return self.emit(&serde_yaml::Value::Mapping(map));
+        };
+
+        // Keys whose value didn't change are printed as written, comments included. That holds
+        // for dates too, which `to_mapping` leaves as written unless a format or zone is set,
+        // except that plain dates lose needless quotes.
+        let mut s = String::new();
+        for (key, value) in map {
+            match source.get(&key) {
+                Some(entry) => {
+                    s.push_str(&entry.leading);
+                    let date = key.as_str().is_some_and(|v| DATE_KEYS.contains(&v));
+                    if entry.value == value && date {
+                        s.push_str(&entry.unquoted().unwrap_or_else(|| entry.text.clone()));
+                    } else if entry.value == value {
+                        s.push_str(&entry.text);
+                    } else {
+                        s.push_str(&self.emit_entry(key, value)?);
+                    }
+                },
+                None => s.push_str(&self.emit_entry(key, value)?),
+            }
+        }
+        s.push_str(&source.trailer);
         Ok(s)
     }
 
+    /// Reads YAML front matter, with its dates read with `options`.
+    pub fn from_yaml(s: &str, options: &DateOptions) -> Result<Self> {
+        let mut value: serde_yaml::Value =
+            serde_yaml::from_str(s).with_context(|| "could not parse front matter".to_string())?;
+        resolve_dates(&mut value, options);
+        let meta: Self = serde_yaml::from_value(value)
+            .with_context(|| "could not parse front matter".to_string())?;
+        Ok(Self {
+            source: Some(s.to_string()),
+            ..meta
+        })
+    }
+
+    pub fn from_toml(s: &str, options: &DateOptions) -> Result<Self> {
+        let value: toml::Value =
+            toml::from_str(s).with_context(|| "could not parse TOML front matter".to_string())?;
+        let mut value = serde_yaml::to_value(toml_to_json(value))
+            .with_context(|| "could not parse TOML front matter".to_string())?;
+        resolve_dates(&mut value, options);
+        let meta: Self = serde_yaml::from_value(value)
+            .with_context(|| "could not parse TOML front matter".to_string())?;
+        Ok(Self {
+            source: Some(s.to_string()),
+            format: FrontMatterFormat::Toml,
+            ..meta
+        })
+    }
+
+    pub fn from_json(s: &str, delimited: bool, options: &DateOptions) -> Result<Self> {
+        let mut value: serde_yaml::Value = serde_json::from_str(s)
+            .with_context(|| "could not parse JSON front matter".to_string())?;
+        resolve_dates(&mut value, options);
+        let meta: Self = serde_yaml::from_value(value)
+            .with_context(|| "could not parse JSON front matter".to_string())?;
+        Ok(Self {
+            source: Some(s.to_string()),
+            format: FrontMatterFormat::Json,
+            json_delimited: delimited,
+            ..meta
+        })
+    }
+
+    pub fn to_toml(&self, options: &FrontMatterOptions) -> Result<String> {
+        let mut value = toml::Value::try_from(self.to_mapping(options)?)
+            .with_context(|| "could not stringify front matter as TOML".to_string())?;
+
+        // Dates written as TOML dates are printed as such, not as strings
+        let source = match self.format {
+            FrontMatterFormat::Toml => self.source.as_deref().and_then(|v| v.parse().ok()),
+            _ => None,
+        };
+        if let (toml::Value::Table(table), Some(toml::Value::Table(source))) = (&mut value, source)
+        {
+            for key in DATE_TIME_KEYS.iter().chain(DATE_KEYS) {
+                let Some(toml::Value::Datetime(written)) = source.get(*key) else {
+                    continue;
+                };
+                if table.get(*key).and_then(|v| v.as_str()) == Some(&written.to_string()) {
+                    table.insert(key.to_string(), toml::Value::Datetime(*written));
+                }
+            }
+        }
+
+        toml::to_string(&value)
+            .with_context(|| "could not stringify front matter as TOML".to_string())
+    }
+
+    pub fn to_json(&self, options: &FrontMatterOptions) -> Result<String> {
+        serde_json::to_string_pretty(&self.to_mapping(options)?)
+            .with_context(|| "could not stringify front matter as JSON".to_string())
+    }
+
+    fn to_mapping(&self, options: &FrontMatterOptions) -> Result<serde_yaml::Mapping> {
+        let mut map = match serde_yaml::to_value(self)
+            .with_context(|| "could not stringify front matter".to_string())?
+        {
+            serde_yaml::Value::Mapping(map) => self.sort_keys(map, options),
+            _ => serde_yaml::Mapping::new(),
+        };
+
+        // Dates are printed as written unless they changed or a format or zone is set
+        let timestamps = &options.timestamps;
+        let source = self.source_mapping();
+        let dates = timestamps.date_options();
+        let written = |key: &str| source.as_ref()?.get(key);
+        for (key, value) in [
+            ("createdAt", self.created_at),
+            ("updatedAt", self.updated_at),
+        ] {
+            let Some(value) = value else { continue };
+            if timestamps.format.is_some() || timestamps.timezone.is_some() {
+                map.insert(key.into(), timestamps.render(&value).into());
+            } else if let Some(v) = written(key).filter(|v| date_time_of(v, &dates) == Some(value))
+            {
+                map.insert(key.into(), v.clone());
+            }
+        }
+        if let Some(value) = self.journal_date {
+            if timestamps.date_format.is_some() {
+                map.insert("journalDate".into(), timestamps.render_date(&value).into());
+            } else if let Some(v) =
+                written("journalDate").filter(|v| date_of(v, &dates) == Some(value))
+            {
+                map.insert("journalDate".into(), v.clone());
+            }
+        }
+        Ok(map)
+    }
+
+    /// The front matter as written, with TOML dates as strings.
+    fn source_mapping(&self) -> Option<serde_yaml::Mapping> {
+        let source = self.source.as_ref()?;
+        match self.format {
+            FrontMatterFormat::Yaml | FrontMatterFormat::Json => serde_yaml::from_str(source).ok(),
+            FrontMatterFormat::Toml => {
+                let value = serde_yaml::to_value(toml_to_json(toml::from_str(source).ok()?));
+                value.ok()?.as_mapping().cloned()
+            },
+        }
+    }
+
+    fn emit_entry(&self, key: serde_yaml::Value, value: serde_yaml::Value) -> Result<String> {
+        self.emit(&serde_yaml::Value::Mapping(serde_yaml::Mapping::from_iter(
+            [(key, value)],
+        )))
+    }
+
+    fn emit(&self, value: &serde_yaml::Value) -> Result<String> {
+        let s = serde_yaml::to_string(value)
+            .with_context(|| "could not stringify front matter".to_string())?;
+        Ok(self.fix_indent(&s))
+    }
+
+    /// Orders keys by `options.key_order`, then by their position in the source when
+    /// `options.keep_order` is set. Other keys keep their place after those.
+    fn sort_keys(
+        &self,
+        map: serde_yaml::Mapping,
+        options: &FrontMatterOptions,
+    ) -> serde_yaml::Mapping {
+        let source_keys = match (self.source_mapping(), options.keep_order) {
+            (Some(source), true) => source.into_iter().map(|(k, _)| k).collect::<Vec<_>>(),
+            _ => vec![],
+        };
+        let rank = |key: &serde_yaml::Value| {
+            (
+                options
+                    .key_order
+                    .iter()
+                    .position(|v| key.as_str() == Some(v))
+                    .unwrap_or(usize::MAX),
+                source_keys
+                    .iter()
+                    .position(|v| v == key)
+                    .unwrap_or(usize::MAX),
+            )
+        };
+
+        let mut entries = map.into_iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(k, _)| rank(k));
+        entries.into_iter().collect()
+    }
+
     fn fix_indent(&self, input: &str) -> String {
         // Workaround
         // https://github.com/dtolnay/serde-yaml/issues/337
@@ -166,10 +453,19 @@ impl Meta {
             updated_at: self.updated_at.or_else(|| bookmark?.updated_at),
             link: self.link.or_else(|| bookmark?.url.clone()),
             bookmark: self.bookmark.and_then(|v| v.normalize()),
-            toc: None,
+            toc: self.toc.filter(|v| v == TOC_AUTO),
             others: self.others,
+            source: self.source,
+            format: self.format,
+            json_delimited: self.json_delimited,
+        };
+        let empty = Self {
+            source: res.source.clone(),
+            format: res.format,
+            json_delimited: res.json_delimited,
+            ..Default::default()
         };
-        if res == Self::default() {
+        if res == empty {
             None
         } else {
             Some(res)
@@ -178,10 +474,29 @@ impl Meta {
 
     pub fn parse_toc(&self) -> Result<Option<Toc>> {
         let Some(v) = &self.toc else { return Ok(None) };
+        if self.is_toc_auto() {
+            return Ok(None);
+        }
 
         let res = Toc::parse(v)?;
         Ok(Some(res))
     }
+
+    pub fn is_toc_auto(&self) -> bool {
+        self.toc.as_deref() == Some(TOC_AUTO)
+    }
+
+    /// Returns `(key, value)` for each `status` or `kind` value this crate doesn't know.
+    pub fn unknown_values(&self) -> Vec<(&'static str, &str)> {
+        let mut res = vec![];
+        if let Some(NoteStatus::Other(v)) = &self.status {
+            res.push(("status", v.as_str()));
+        }
+        if let Some(NoteKind::Other(v)) = &self.kind {
+            res.push(("kind", v.as_str()));
+        }
+        res
+    }
 }
 
 impl Bookmark {
@@ -226,6 +541,8 @@ pub enum NoteStatus {
     Done,
     NotPlanned,
     Archived,
+    /// A value this crate doesn't know, kept as written.
+    Other(String),
 }
 
 impl std::fmt::Display for NoteStatus {
@@ -236,6 +553,7 @@ impl std::fmt::Display for NoteStatus {
             Self::Done => write!(f, "done"),
             Self::NotPlanned => write!(f, "not planned"),
             Self::Archived => write!(f, "archived"),
+            Self::Other(v) => write!(f, "{v}"),
         }
     }
 }
@@ -250,7 +568,7 @@ impl std::str::FromStr for NoteStatus {
             "done" => Ok(Self::Done),
             "not planned" => Ok(Self::NotPlanned),
             "archived" => Ok(Self::Archived),
-            _ => Ok(Self::Todo),
+            _ => Ok(Self::Other(s.to_string())),
         }
     }
 }
@@ -260,8 +578,10 @@ mod tests {
     use pretty_assertions::assert_eq;
     use serde_json::json;
 
+    use std::str::FromStr;
+
     use super::*;
-    use crate::NoteKind;
+    use crate::{NoteKind, TimestampOptions};
 
     #[test]
     fn normalize_empty() {
@@ -368,6 +688,105 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn unknown_values() -> Result<()> {
+        let meta = serde_json::from_value::<Meta>(json!({
+            "kind": "recipe",
+            "status": "blocked"
+        }))?;
+        assert_eq!(meta.status, Some(NoteStatus::Other("blocked".into())));
+        assert_eq!(
+            meta.unknown_values(),
+            vec![("status", "blocked"), ("kind", "recipe")]
+        );
+        assert_eq!(
+            serde_json::to_value(&meta)?,
+            json!({
+                "kind": "recipe",
+                "status": "blocked"
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn key_order() -> Result<()> {
+        let meta = Meta::from_str("zzz: 1\ntags: [a]\ntitle: foo\naaa: 2\n")?;
+        assert_eq!(
+            meta.to_md(&FrontMatterOptions::default())?,
+            "title: foo\ntags: [a]\naaa: 2\nzzz: 1\n"
+        );
+        assert_eq!(
+            meta.to_md(&FrontMatterOptions {
+                key_order: vec!["aaa".into(), "title".into()],
+                ..Default::default()
+            })?,
+            "aaa: 2\ntitle: foo\ntags: [a]\nzzz: 1\n"
+        );
+        assert_eq!(
+            meta.to_md(&FrontMatterOptions {
+                key_order: vec!["title".into()],
+                keep_order: true,
+                ..Default::default()
+            })?,
+            "title: foo\nzzz: 1\ntags: [a]\naaa: 2\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn convert_format() -> Result<()> {
+        let meta = Meta::from_toml(
+            "title = \"foo\"\ntags = [\"a\"]\njournalDate = 2000-01-01\n",
+            &DateOptions::default(),
+        )?;
+        assert_eq!(meta.format, FrontMatterFormat::Toml);
+        assert_eq!(meta.journal_date, NaiveDate::from_ymd_opt(2000, 1, 1));
+
+        let yaml = FrontMatterOptions {
+            format: Some(FrontMatterFormat::Yaml),
+            ..Default::default()
+        };
+        assert_eq!(
+            Metadata::Meta(meta.clone()).to_md(&yaml)?,
+            "---\ntitle: foo\njournalDate: 2000-01-01\ntags:\n  - a\n---"
+        );
+        assert_eq!(
+            Metadata::Meta(meta.clone()).to_md(&FrontMatterOptions::default())?,
+            "+++\ntitle = \"foo\"\njournalDate = 2000-01-01\ntags = [\"a\"]\n+++"
+        );
+
+        let meta = Meta::from_json(r#"{"title": "foo"}"#, false, &DateOptions::default())?;
+        assert_eq!(meta.format, FrontMatterFormat::Json);
+        assert_eq!(
+            Metadata::Meta(meta).to_md(&FrontMatterOptions::default())?,
+            "{\n  \"title\": \"foo\"\n}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn timestamp_format() -> Result<()> {
+        let meta = Meta::from_str("createdAt: 2000-01-01T15:00:00Z\n")?;
+        let options = |format: Option<&str>| FrontMatterOptions {
+            timestamps: TimestampOptions {
+                format: format.map(String::from),
+                timezone: "+09:00".parse().ok(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            meta.to_md(&options(None))?,
+            "createdAt: \"2000-01-02T00:00:00+09:00\"\n"
+        );
+        assert_eq!(
+            meta.to_md(&options(Some("%Y-%m-%d %H:%M")))?,
+            "createdAt: \"2000-01-02 00:00\"\n"
+        );
+        Ok(())
+    }
+
     #[test]
     fn deserialize() -> Result<()> {
         assert_eq!(
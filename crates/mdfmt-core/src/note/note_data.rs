@@ -5,7 +5,11 @@ use tsify::Tsify;
 use super::{
     block::Block,
     metadata::{Meta, Metadata},
+    options::FormatOptions,
+    slug::assign_slugs,
+    toc::{number_headings, Toc},
     visitor::{Visitor, VisitorContext},
+    wiki_link::WikiLink,
 };
 use crate::{debug_printer::DebugPrinter, printer::Printer};
 
@@ -13,23 +17,70 @@ use crate::{debug_printer::DebugPrinter, printer::Printer};
 pub struct Note {
     pub metadata: Option<Metadata>,
     pub body: Vec<Block>,
+    /// Wiki links in the body, in order. They're printed from the text of `body`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wiki_links: Vec<WikiLink>,
 }
 
 impl Note {
     pub fn new(metadata: Option<Metadata>, body: Vec<Block>) -> Self {
-        Self { metadata, body }
+        Self {
+            metadata,
+            body,
+            wiki_links: vec![],
+        }
     }
 
-    pub fn normalize(self) -> Result<Self> {
-        let mut body = self.get_toc()?;
+    pub fn normalize(self, options: &FormatOptions) -> Result<Self> {
+        let toc_auto = self.is_toc_auto();
+        let mut body = if toc_auto { vec![] } else { self.get_toc()? };
         body.extend(self.body);
 
+        if options.toc.number_headings {
+            number_headings(&mut body, &options.toc, options.heading_level);
+            assign_slugs(&mut body, &options.slug_style);
+        }
+
+        if toc_auto || options.toc.auto {
+            let toc = Block::toc(
+                Toc::from_sections(&body, &options.toc, options.heading_level).flatten_ref(),
+            );
+            if !replace_toc(&mut body, &toc) && toc_auto {
+                body.insert(0, toc);
+            }
+        }
+
         Ok(Self {
-            metadata: self.metadata.and_then(Metadata::normalize),
+            metadata: self
+                .metadata
+                .and_then(Metadata::normalize)
+                .map(|v| match v {
+                    Metadata::Meta(meta) => Metadata::Meta(Meta {
+                        status: meta.status.map(|v| options.status.resolve(v)),
+                        ..meta
+                    }),
+                    v => v,
+                }),
             body,
+            wiki_links: self.wiki_links,
         })
     }
 
+    /// The front matter, created if missing. `None` if it couldn't be parsed.
+    pub fn meta_mut(&mut self) -> Option<&mut Meta> {
+        let metadata = self
+            .metadata
+            .get_or_insert_with(|| Metadata::Meta(Meta::default()));
+        match metadata {
+            Metadata::Meta(meta) => Some(meta),
+            _ => None,
+        }
+    }
+
+    fn is_toc_auto(&self) -> bool {
+        matches!(&self.metadata, Some(Metadata::Meta(m)) if m.is_toc_auto())
+    }
+
     fn get_toc(&self) -> Result<Vec<Block>> {
         match &self.metadata {
             Some(Metadata::Meta(Meta {
@@ -53,11 +104,28 @@ impl Note {
     }
 }
 
+/// Replaces every TOC block with `toc`. Returns whether any was found.
+fn replace_toc(blocks: &mut [Block], toc: &Block) -> bool {
+    let mut found = false;
+    for block in blocks {
+        match block {
+            Block::Toc(_) => {
+                *block = toc.clone();
+                found = true;
+            },
+            Block::AnonymousSection(children) => found |= replace_toc(children, toc),
+            Block::Section(section) => found |= replace_toc(&mut section.children, toc),
+            _ => {},
+        }
+    }
+    found
+}
+
 impl Printer for Note {
-    type Options = ();
+    type Options = FormatOptions;
 
-    fn print(&self, _options: Self::Options) -> Result<String> {
-        let context = &mut VisitorContext::new(1);
+    fn print(&self, options: Self::Options) -> Result<String> {
+        let context = &mut VisitorContext::new(1, &options);
 
         if let Some(metadata) = &self.metadata {
             metadata.visit(context)?;
@@ -70,6 +138,21 @@ impl Printer for Note {
     }
 }
 
+impl DebugPrinter for Note {
+    type Options = ();
+
+    fn debug_print(&self, _options: Self::Options) -> String {
+        let mut s = String::new();
+
+        s.push_str(&format!("{:?}\n---\n", self.metadata));
+        for block in self.body.iter() {
+            s.push_str(&block.debug_print(0));
+        }
+
+        s
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use indoc::indoc;
@@ -77,8 +160,9 @@ mod tests {
 
     use super::super::{
         block::Block,
-        metadata::{Bookmark, Meta, Metadata},
+        metadata::{Bookmark, Meta, Metadata, TOC_AUTO},
         note_kind::NoteKind,
+        options::{HeadingLevel, TocOptions},
     };
     use super::*;
 
@@ -92,7 +176,7 @@ mod tests {
                 })),
                 vec![]
             )
-            .print(())?,
+            .print(FormatOptions::default())?,
             indoc! {"
                 ---
                 title: foo
@@ -114,9 +198,9 @@ mod tests {
             })),
             vec![],
         )
-        .normalize()?;
+        .normalize(&FormatOptions::default())?;
         assert_eq!(
-            &note.print(())?,
+            &note.print(FormatOptions::default())?,
             indoc! {"
                 > [!toc]
                 > - aaa
@@ -126,10 +210,65 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn convert_toc_auto() -> Result<()> {
+        let body = vec![
+            Block::anonymous_section(vec![Block::toc(vec![])]),
+            Block::section(1, "aaa", vec![Block::section(2, "bbb", vec![])]),
+        ];
+        let expected = indoc! {"
+            > [!toc]
+            > - aaa
+            >     - bbb
+
+            # aaa
+            ## bbb
+        "};
+
+        let note = Note::new(None, body.clone()).normalize(&FormatOptions::default())?;
+        assert_eq!(
+            &note.print(FormatOptions::default())?,
+            indoc! {"
+                > [!toc]
+
+                # aaa
+                ## bbb
+            "}
+        );
+
+        let options = FormatOptions {
+            toc: TocOptions {
+                auto: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let note = Note::new(None, body).normalize(&options)?;
+        assert_eq!(&note.print(options)?, expected);
+
+        let note = Note::new(
+            Some(Metadata::Meta(Meta {
+                toc: Some(TOC_AUTO.into()),
+                ..Default::default()
+            })),
+            vec![Block::section(
+                1,
+                "aaa",
+                vec![Block::section(2, "bbb", vec![])],
+            )],
+        )
+        .normalize(&FormatOptions::default())?;
+        assert_eq!(
+            &note.print(FormatOptions::default())?,
+            &format!("---\ntoc: auto\n---\n{expected}")
+        );
+        Ok(())
+    }
+
     #[test]
     fn convert_head_text() -> Result<()> {
         assert_eq!(
-            Note::new(None, vec![Block::text("foo")]).print(())?,
+            Note::new(None, vec![Block::text("foo")]).print(FormatOptions::default())?,
             indoc! {"
                 foo
             "},
@@ -143,11 +282,12 @@ mod tests {
             Note::new(
                 None,
                 vec![Block::anonymous_section(vec![Block::section(
+                    2,
                     "heading",
                     vec![Block::text("foo")]
                 )])],
             )
-            .print(())?,
+            .print(FormatOptions::default())?,
             indoc! {"
                 ## heading
                 foo
@@ -161,9 +301,9 @@ mod tests {
         assert_eq!(
             Note::new(
                 None,
-                vec![Block::section("heading", vec![Block::text("foo")])],
+                vec![Block::section(1, "heading", vec![Block::text("foo")])],
             )
-            .print(())?,
+            .print(FormatOptions::default())?,
             indoc! {"
                 # heading
                 foo
@@ -175,7 +315,8 @@ mod tests {
     #[test]
     fn convert_body_text() -> Result<()> {
         assert_eq!(
-            Note::new(None, vec![Block::section("foo", vec![])]).print(())?,
+            Note::new(None, vec![Block::section(1, "foo", vec![])])
+                .print(FormatOptions::default())?,
             indoc! {"
                 # foo
             "}
@@ -183,6 +324,36 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn convert_heading_depth() -> Result<()> {
+        let note = Note::new(
+            None,
+            vec![Block::section(
+                1,
+                "foo",
+                vec![Block::section(3, "bar", vec![])],
+            )],
+        );
+        assert_eq!(
+            note.print(FormatOptions::default())?,
+            indoc! {"
+                # foo
+                ## bar
+            "}
+        );
+        assert_eq!(
+            note.print(FormatOptions {
+                heading_level: HeadingLevel::Keep,
+                ..Default::default()
+            })?,
+            indoc! {"
+                # foo
+                ### bar
+            "}
+        );
+        Ok(())
+    }
+
     #[test]
     fn convert_card() -> Result<()> {
         assert_eq!(
@@ -198,7 +369,7 @@ mod tests {
                     Block::card(NoteKind::Todo, None, vec![]),
                 ],
             )
-            .print(())?,
+            .print(FormatOptions::default())?,
             indoc! {"
                 > [!note]
 
@@ -218,18 +389,3 @@ mod tests {
         Ok(())
     }
 }
-
-impl DebugPrinter for Note {
-    type Options = ();
-
-    fn debug_print(&self, _options: Self::Options) -> String {
-        let mut s = String::new();
-
-        s.push_str(&format!("{:?}\n---\n", self.metadata));
-        for block in self.body.iter() {
-            s.push_str(&block.debug_print(0));
-        }
-
-        s
-    }
-}
@@ -11,6 +11,8 @@ pub enum NoteKind {
     Question,
     Toc,
     Todo,
+    /// A value this crate doesn't know, kept as written.
+    Other(String),
 }
 
 impl std::fmt::Display for NoteKind {
@@ -22,6 +24,7 @@ impl std::fmt::Display for NoteKind {
             Self::Question => write!(f, "question"),
             Self::Toc => write!(f, "toc"),
             Self::Todo => write!(f, "todo"),
+            Self::Other(v) => write!(f, "{v}"),
         }
     }
 }
@@ -37,7 +40,7 @@ impl std::str::FromStr for NoteKind {
             "question" => Ok(Self::Question),
             "toc" => Ok(Self::Toc),
             "todo" => Ok(Self::Todo),
-            _ => Ok(Self::Note),
+            _ => Ok(Self::Other(s.to_string())),
         }
     }
 }
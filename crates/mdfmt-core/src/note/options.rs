@@ -0,0 +1,295 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, Offset, SecondsFormat, Utc};
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+use super::{slug::SlugStyle, status::StatusWorkflow};
+use crate::date::DateOptions;
+
+#[derive(Debug, Default, Clone)]
+pub struct FormatOptions {
+    pub heading_level: HeadingLevel,
+    pub slug_style: SlugStyle,
+    pub toc: TocOptions,
+    pub status: StatusWorkflow,
+    pub front_matter: FrontMatterOptions,
+    pub journal: JournalOptions,
+    pub index: IndexOptions,
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct IndexOptions {
+    /// Leave out notes without front matter instead of deriving their title and dates.
+    pub metadata_only: bool,
+    /// Add the outline, word counts, tasks, cards and links of each note.
+    pub details: bool,
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct JournalOptions {
+    /// Paths of daily notes, e.g. `{year}-{month}-{day}.md` or `journal/{year}/{month}/{day}.md`.
+    pub patterns: Vec<String>,
+    /// Write the date from `patterns` into `journalDate` when formatting.
+    pub write: bool,
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct FrontMatterOptions {
+    /// Keys printed first, in this order, e.g. `title`, `aliases`, `tags`.
+    pub key_order: Vec<String>,
+    /// Print the remaining keys in the order they were written.
+    pub keep_order: bool,
+    /// Convert front matter to this format. Each note keeps its own format when unset.
+    pub format: Option<FrontMatterFormat>,
+    pub timestamps: TimestampOptions,
+}
+
+#[serde_as]
+#[derive(PartialEq, Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct TimestampOptions {
+    /// Fill a missing `createdAt`, and set `updatedAt` when writing a changed body.
+    pub auto: bool,
+    /// Where a missing `createdAt` comes from.
+    pub created_from: CreatedSource,
+    /// `strftime` format of `createdAt` and `updatedAt`. RFC 3339 when unset.
+    pub format: Option<String>,
+    /// `strftime` format of `journalDate`. `%Y-%m-%d` when unset.
+    pub date_format: Option<String>,
+    /// `strftime` formats accepted in date fields besides the built-in ones.
+    pub input_formats: Vec<String>,
+    /// Offset `createdAt` and `updatedAt` are printed in, e.g. `+09:00`. Each keeps its own
+    /// when unset.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub timezone: Option<FixedOffset>,
+    /// Offset of dates and times written without one. UTC when unset.
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub default_timezone: Option<FixedOffset>,
+}
+
+impl TimestampOptions {
+    /// How front matter dates are read.
+    pub fn date_options(&self) -> DateOptions {
+        DateOptions {
+            offset: self.default_offset(),
+            formats: self.input_formats.clone(),
+        }
+    }
+
+    pub fn default_offset(&self) -> FixedOffset {
+        self.default_timezone.unwrap_or_else(|| Utc.fix())
+    }
+
+    pub fn render_date(&self, value: &NaiveDate) -> String {
+        value
+            .format(self.date_format.as_deref().unwrap_or("%Y-%m-%d"))
+            .to_string()
+    }
+
+    pub fn render(&self, value: &DateTime<FixedOffset>) -> String {
+        let offset = self.timezone.unwrap_or_else(|| *value.offset());
+        let value = value.with_timezone(&offset);
+        match &self.format {
+            Some(format) => value.format(format).to_string(),
+            None => value.to_rfc3339_opts(SecondsFormat::AutoSi, offset == Utc.fix()),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CreatedSource {
+    /// The author date of the commit that added the file, or `Ctime` outside git
+    #[default]
+    Git,
+    /// The file's creation (birth) time, not the Unix ctime, which changes with the file's
+    /// metadata. Its modification time where the file system doesn't record one.
+    Ctime,
+}
+
+impl std::fmt::Display for CreatedSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Git => write!(f, "git"),
+            Self::Ctime => write!(f, "ctime"),
+        }
+    }
+}
+
+impl std::str::FromStr for CreatedSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "git" => Ok(Self::Git),
+            "ctime" => Ok(Self::Ctime),
+            _ => Err(anyhow!("unknown createdAt source `{s}`")),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontMatterFormat {
+    /// Between `---` lines
+    #[default]
+    Yaml,
+    /// Between `+++` lines
+    Toml,
+    /// A JSON object, optionally between `;;;` lines
+    Json,
+}
+
+impl std::fmt::Display for FrontMatterFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Yaml => write!(f, "yaml"),
+            Self::Toml => write!(f, "toml"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+impl std::str::FromStr for FrontMatterFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "yaml" => Ok(Self::Yaml),
+            "toml" => Ok(Self::Toml),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow!("unknown front matter format `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TocOptions {
+    /// Fill every `> [!toc]` block from the note's headings, not only when `toc: auto` is set.
+    pub auto: bool,
+    /// Shallowest heading level listed in a generated TOC.
+    pub min_depth: u8,
+    /// Deepest heading level listed in a generated TOC.
+    pub max_depth: u8,
+    /// How generated TOC entries link to their headings.
+    pub link: TocLinkStyle,
+    /// List style of generated TOC entries.
+    pub style: TocStyle,
+    /// Prefix headings between `min_depth` and `max_depth` with numbers such as `1.2.`.
+    pub number_headings: bool,
+}
+
+impl Default for TocOptions {
+    fn default() -> Self {
+        Self {
+            auto: false,
+            min_depth: 1,
+            max_depth: 6,
+            link: TocLinkStyle::default(),
+            style: TocStyle::default(),
+            number_headings: false,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub enum TocLinkStyle {
+    /// `- Heading`
+    #[default]
+    None,
+    /// `- [[#Heading]]`
+    Obsidian,
+    /// `- [Heading](#slug)`
+    Markdown,
+}
+
+impl std::fmt::Display for TocLinkStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "none"),
+            Self::Obsidian => write!(f, "obsidian"),
+            Self::Markdown => write!(f, "markdown"),
+        }
+    }
+}
+
+impl std::str::FromStr for TocLinkStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "none" => Ok(Self::None),
+            "obsidian" => Ok(Self::Obsidian),
+            "markdown" => Ok(Self::Markdown),
+            _ => Err(anyhow!("unknown TOC link style `{s}`")),
+        }
+    }
+}
+
+/// How heading levels are printed.
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub enum HeadingLevel {
+    /// Derive levels from the section nesting, so `#` followed by `###` becomes `#` and `##`.
+    #[default]
+    Normalize,
+    /// Print each heading with the level it had in the source.
+    Keep,
+}
+
+impl std::fmt::Display for HeadingLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Normalize => write!(f, "normalize"),
+            Self::Keep => write!(f, "keep"),
+        }
+    }
+}
+
+impl std::str::FromStr for HeadingLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "normalize" => Ok(Self::Normalize),
+            "keep" => Ok(Self::Keep),
+            _ => Err(anyhow!("unknown heading level `{s}`")),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Copy)]
+pub enum TocStyle {
+    /// `- Heading`
+    #[default]
+    Bullet,
+    /// `1. Heading`
+    Ordered,
+    /// `- 1.2 Heading`
+    Hierarchical,
+}
+
+impl std::fmt::Display for TocStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Bullet => write!(f, "bullet"),
+            Self::Ordered => write!(f, "ordered"),
+            Self::Hierarchical => write!(f, "hierarchical"),
+        }
+    }
+}
+
+impl std::str::FromStr for TocStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "bullet" => Ok(Self::Bullet),
+            "ordered" => Ok(Self::Ordered),
+            "hierarchical" => Ok(Self::Hierarchical),
+            _ => Err(anyhow!("unknown TOC style `{s}`")),
+        }
+    }
+}
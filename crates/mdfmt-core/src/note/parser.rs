@@ -1,15 +1,26 @@
 use std::iter::Peekable;
 use std::slice::Iter;
-use std::str::FromStr;
 
 use anyhow::{anyhow, Ok, Result};
 use itertools::Itertools;
 use markdown::mdast::{self as m, Paragraph};
 
-use super::{block::Block, metadata::Metadata, note_data::Note, note_kind::NoteKind, toc::Toc};
-use crate::printer::Printer;
-
-pub struct NoteParser {}
+use super::{
+    block::Block,
+    metadata::{Meta, Metadata},
+    note_data::Note,
+    note_kind::NoteKind,
+    options::FormatOptions,
+    section::Section,
+    slug::assign_slugs,
+    toc::Toc,
+    wiki_link::WikiLink,
+};
+use crate::{ast::wiki_link::wiki_link_of, printer::Printer, JsonFrontMatter};
+
+pub struct NoteParser<'a> {
+    options: &'a FormatOptions,
+}
 
 const KIND_LIST: &[(&str, &NoteKind)] = &[
     ("[!note]", &NoteKind::Note),
@@ -20,38 +31,90 @@ const KIND_LIST: &[(&str, &NoteKind)] = &[
     ("[!todo]", &NoteKind::Todo),
 ];
 
-impl NoteParser {
+impl<'a> NoteParser<'a> {
     pub fn parse(node: &m::Node) -> Result<Note> {
-        let parser = Self {};
-        parser.parse_root(node)
+        NoteParser::parse_with(node, &FormatOptions::default())
+    }
+
+    pub fn parse_with(node: &m::Node, options: &'a FormatOptions) -> Result<Note> {
+        Self::parse_with_json(node, None, options)
+    }
+
+    /// Parses a note whose JSON front matter was split off before parsing `node`.
+    pub(crate) fn parse_with_json(
+        node: &m::Node,
+        json: Option<&JsonFrontMatter>,
+        options: &'a FormatOptions,
+    ) -> Result<Note> {
+        Self::new(options).parse_root(node, json)
+    }
+
+    pub(crate) fn new(options: &'a FormatOptions) -> Self {
+        Self { options }
+    }
+
+    /// The kind of a card with only a title line, e.g. `> [!note] Foo`.
+    pub(crate) fn empty_card(&self, block_quote: &m::BlockQuote) -> Option<NoteKind> {
+        let (first, rest) = block_quote.children.split_first()?;
+        match self.parse_card(first)? {
+            (kind, _, None) if rest.is_empty() && kind != NoteKind::Toc => Some(kind),
+            _ => None,
+        }
     }
 
-    fn parse_root(&self, node: &m::Node) -> Result<Note> {
+    fn parse_root(&self, node: &m::Node, json: Option<&JsonFrontMatter>) -> Result<Note> {
         match node {
             m::Node::Root(node) => {
                 let mut iter = node.children.iter().peekable();
 
-                let metadata = self.parse_metadata(&mut iter)?;
+                let metadata = match json {
+                    Some(json) => Some(self.parse_json_metadata(json)),
+                    None => self.parse_metadata(&mut iter)?,
+                };
                 let mut body = self.parse_head_block(&mut iter)?;
                 let rest = self.parse_block(&mut iter, 0)?;
                 body.extend(rest);
-
-                Ok(Note { metadata, body })
+                assign_slugs(&mut body, &self.options.slug_style);
+
+                let mut wiki_links = vec![];
+                for child in &node.children {
+                    collect_wiki_links(child, &mut wiki_links);
+                }
+
+                Ok(Note {
+                    metadata,
+                    body,
+                    wiki_links,
+                })
             },
             _ => Err(anyhow!("invalid")),
         }
     }
 
     fn parse_metadata(&self, iter: &mut Peekable<Iter<m::Node>>) -> Result<Option<Metadata>> {
-        let Some(m::Node::Yaml(node)) = iter.peek() else {
-            return Ok(None);
+        let dates = self.options.front_matter.timestamps.date_options();
+        let metadata = match iter.peek() {
+            Some(m::Node::Yaml(node)) => Ok(Meta::from_yaml(&node.value, &dates)
+                .map(Metadata::Meta)
+                .unwrap_or_else(|_| Metadata::Raw(node.value.to_owned()))),
+            Some(m::Node::Toml(node)) => Ok(Meta::from_toml(&node.value, &dates)
+                .map(Metadata::Meta)
+                .unwrap_or_else(|_| Metadata::RawToml(node.value.to_owned()))),
+            _ => return Ok(None),
         };
 
         iter.next();
-        Ok(Some(
-            Metadata::from_str(&node.value)
-                .unwrap_or_else(|_| Metadata::Raw(node.value.to_owned())),
-        ))
+        metadata.map(Some)
+    }
+
+    fn parse_json_metadata(&self, json: &JsonFrontMatter) -> Metadata {
+        let dates = self.options.front_matter.timestamps.date_options();
+        Meta::from_json(json.value, json.delimited, &dates)
+            .map(Metadata::Meta)
+            .unwrap_or_else(|_| match json.delimited {
+                true => Metadata::RawJson(format!(";;;\n{}\n;;;", json.value)),
+                false => Metadata::RawJson(json.value.to_owned()),
+            })
     }
 
     fn parse_head_block(&self, iter: &mut Peekable<Iter<m::Node>>) -> Result<Vec<Block>> {
@@ -73,9 +136,11 @@ impl NoteParser {
                 },
                 m::Node::Heading(node) => {
                     iter.next();
-                    let title = self.parse_heading(node)?;
+                    let (title, raw_title) = self.parse_heading(node)?;
                     let children = self.parse_block(iter, node.depth)?;
-                    res.push(Block::section(&title, children));
+                    res.push(Block::Section(Section::with_raw_title(
+                        node.depth, &title, &raw_title, children,
+                    )));
                 },
                 m::Node::BlockQuote(node) => {
                     iter.next();
@@ -141,9 +206,7 @@ impl NoteParser {
             return None;
         };
 
-        let Some((kind, title, s)) = self.parse_card_paragraph(value) else {
-            return None;
-        };
+        let (kind, title, s) = self.parse_card_paragraph(value)?;
 
         if s.is_empty() && rest.is_empty() {
             return Some((kind, title, None));
@@ -190,14 +253,23 @@ impl NoteParser {
         None
     }
 
-    fn parse_heading(&self, heading: &m::Heading) -> Result<String> {
-        let mut res: Vec<String> = vec![];
+    fn parse_heading(&self, heading: &m::Heading) -> Result<(String, String)> {
+        let title = heading
+            .children
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<String>();
+        let raw_title = heading.children.print(())?;
 
-        for node in heading.children.iter() {
-            res.push(node.print(())?);
-        }
+        Ok((title.trim().to_string(), raw_title))
+    }
+}
 
-        Ok(res.join(" "))
+/// Wiki links split off by `wiki_link::split`, leaving out code and escaped links.
+fn collect_wiki_links(node: &m::Node, res: &mut Vec<WikiLink>) {
+    res.extend(wiki_link_of(node));
+    for child in node.children().into_iter().flatten() {
+        collect_wiki_links(child, res);
     }
 }
 
@@ -206,7 +278,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
-    use crate::{ast::builder::*, note::Meta, FlattenNode};
+    use crate::{ast::builder::*, note::Meta, sections, FlattenNode, ListStyle};
 
     #[test]
     fn text_to_invalid() {
@@ -233,6 +305,7 @@ mod tests {
             Note::new(
                 None,
                 vec![Block::anonymous_section(vec![Block::section(
+                    2,
                     "foo",
                     vec![]
                 )])]
@@ -245,7 +318,7 @@ mod tests {
     fn heading_1_to_note() -> Result<()> {
         assert_eq!(
             NoteParser::parse(&root(vec![heading(1, vec![text("foo")])]))?,
-            Note::new(None, vec![Block::section("foo", vec![])]),
+            Note::new(None, vec![Block::section(1, "foo", vec![])]),
         );
         Ok(())
     }
@@ -259,7 +332,11 @@ mod tests {
             ]))?,
             Note::new(
                 None,
-                vec![Block::section("foo", vec![Block::section("bar", vec![])])]
+                vec![Block::section(
+                    1,
+                    "foo",
+                    vec![Block::section(2, "bar", vec![])]
+                )]
             ),
         );
         Ok(())
@@ -277,8 +354,8 @@ mod tests {
             Note::new(
                 None,
                 vec![
-                    Block::section("aaa", vec![Block::section("bbb", vec![])]),
-                    Block::section("ccc", vec![Block::section("ddd", vec![])])
+                    Block::section(1, "aaa", vec![Block::section(2, "bbb", vec![])]),
+                    Block::section(1, "ccc", vec![Block::section(2, "ddd", vec![])])
                 ]
             ),
         );
@@ -295,14 +372,54 @@ mod tests {
             Note::new(
                 None,
                 vec![
-                    Block::anonymous_section(vec![Block::section("foo", vec![])]),
-                    Block::section("bar", vec![])
+                    Block::anonymous_section(vec![Block::section(2, "foo", vec![])]),
+                    Block::section(1, "bar", vec![])
                 ],
             ),
         );
         Ok(())
     }
 
+    #[test]
+    fn heading_with_inline_nodes_to_note() -> Result<()> {
+        assert_eq!(
+            NoteParser::parse(&root(vec![heading(
+                1,
+                vec![
+                    strong(vec![text("foo")]),
+                    text(" bar "),
+                    inline_code("baz"),
+                    text("!")
+                ]
+            )]))?,
+            Note::new(
+                None,
+                vec![Block::Section(Section::with_raw_title(
+                    1,
+                    "foo bar baz!",
+                    "**foo** bar `baz`!",
+                    vec![]
+                ))]
+            ),
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_headings_to_note() -> Result<()> {
+        let note = NoteParser::parse(&root(vec![
+            heading(1, vec![text("foo")]),
+            heading(2, vec![text("Foo")]),
+            heading(1, vec![text("foo")]),
+        ]))?;
+        let slugs = sections(&note.body)
+            .iter()
+            .map(|v| v.slug.clone())
+            .collect::<Vec<String>>();
+        assert_eq!(slugs, vec!["foo", "foo-1", "foo-2"]);
+        Ok(())
+    }
+
     #[test]
     fn block_quote_paragraph_to_note() -> Result<()> {
         assert_eq!(
@@ -329,7 +446,7 @@ mod tests {
                     Block::card(NoteKind::Summary, None, vec![Block::text("foo")]),
                     Block::card(NoteKind::Quote, None, vec![Block::text("foo")]),
                     Block::card(NoteKind::Question, None, vec![Block::text("foo")]),
-                    Block::toc(vec![FlattenNode(1, String::from("foo"))]),
+                    Block::toc(vec![FlattenNode(1, String::from("foo"), ListStyle::Bullet)]),
                     Block::card(NoteKind::Todo, None, vec![Block::text("foo")]),
                 ])],
             )
@@ -344,6 +461,7 @@ mod tests {
             Note::new(
                 Some(Metadata::Meta(Meta {
                     title: Some("foo".into()),
+                    source: Some("title: foo".into()),
                     ..Default::default()
                 })),
                 vec![]
@@ -363,4 +481,47 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn wiki_links_to_note() -> Result<()> {
+        let note = NoteParser::parse(&root(vec![
+            paragraph(vec![
+                text("see "),
+                text("[[foo|Foo]]"),
+                text(" and "),
+                inline_code("[[bar]]"),
+            ]),
+            heading(2, vec![text("![[baz#^id]]")]),
+        ]))?;
+        assert_eq!(
+            note.wiki_links,
+            vec![
+                WikiLink {
+                    target: "foo".into(),
+                    alias: Some("Foo".into()),
+                    ..Default::default()
+                },
+                WikiLink {
+                    target: "baz".into(),
+                    block: Some("id".into()),
+                    embed: true,
+                    ..Default::default()
+                },
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn escaped_wiki_links_to_note() -> Result<()> {
+        let note = crate::parse("see \\[[foo]] and [[bar]]\n")?;
+        assert_eq!(
+            note.wiki_links,
+            vec![WikiLink {
+                target: "bar".into(),
+                ..Default::default()
+            }]
+        );
+        Ok(())
+    }
 }
@@ -1,19 +1,135 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use tsify::Tsify;
 
-use super::block::Block;
+use super::{block::Block, options::HeadingLevel, slug::SlugStyle};
+use crate::to_mdast_from_str;
 
-#[derive(PartialEq, Debug, Default, Clone, Serialize, Deserialize, Tsify)]
+#[derive(PartialEq, Debug, Default, Clone, Serialize, Tsify)]
 pub struct Section {
+    /// Heading level in the source, from 1 to 6. 0 if unknown, which prints at the level of
+    /// its place in the note.
+    #[tsify(optional)]
+    pub depth: u8,
+    /// Heading text without markdown syntax.
     pub title: String,
+    /// Heading content as written in markdown. It's printed unless `title` was changed.
+    #[tsify(optional)]
+    pub raw_title: String,
+    /// Anchor for links to this heading, unique within the note.
+    #[tsify(optional)]
+    pub slug: String,
     pub children: Vec<Block>,
 }
 
 impl Section {
-    pub fn new(title: &str, children: Vec<Block>) -> Self {
+    pub fn new(depth: u8, title: &str, children: Vec<Block>) -> Self {
         Self {
+            depth,
             title: title.to_string(),
+            raw_title: title.to_string(),
+            slug: SlugStyle::default().slugify(title),
             children,
         }
     }
+
+    pub fn with_raw_title(depth: u8, title: &str, raw_title: &str, children: Vec<Block>) -> Self {
+        Self {
+            depth,
+            title: title.to_string(),
+            raw_title: raw_title.to_string(),
+            slug: SlugStyle::default().slugify(title),
+            children,
+        }
+    }
+
+    /// Level the heading is printed at, when its section is nested `depth` deep.
+    pub fn level(&self, depth: u8, heading_level: HeadingLevel) -> u8 {
+        match heading_level {
+            HeadingLevel::Keep if self.depth > 0 => self.depth,
+            _ => depth,
+        }
+    }
+
+    /// `raw_title`, or `title` if it no longer matches, e.g. after editing a note's JSON.
+    pub fn printed_title(&self) -> &str {
+        if self.raw_title == self.title || plain_text(&self.raw_title) == self.title {
+            &self.raw_title
+        } else {
+            &self.title
+        }
+    }
+}
+
+/// Heading content without markdown syntax, as the parser makes `title`.
+fn plain_text(raw_title: &str) -> String {
+    let Ok(node) = to_mdast_from_str(&format!("# {raw_title}")) else {
+        return String::new();
+    };
+    node.children()
+        .and_then(|v| v.first())
+        .and_then(|v| v.children())
+        .map(|v| v.iter().map(|v| v.to_string()).collect::<String>())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+/// Fields added later are optional, so that notes in older JSON can still be printed.
+impl<'de> Deserialize<'de> for Section {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Fields {
+            #[serde(default)]
+            depth: u8,
+            title: String,
+            raw_title: Option<String>,
+            slug: Option<String>,
+            children: Vec<Block>,
+        }
+
+        let v = Fields::deserialize(deserializer)?;
+        Ok(Self {
+            depth: v.depth,
+            raw_title: v.raw_title.unwrap_or_else(|| v.title.clone()),
+            slug: v
+                .slug
+                .unwrap_or_else(|| SlugStyle::default().slugify(&v.title)),
+            title: v.title,
+            children: v.children,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_without_new_fields() -> serde_json::Result<()> {
+        let section: Section = serde_json::from_str(r#"{ "title": "Foo Bar", "children": [] }"#)?;
+        assert_eq!(
+            section,
+            Section {
+                depth: 0,
+                title: "Foo Bar".into(),
+                raw_title: "Foo Bar".into(),
+                slug: "foo-bar".into(),
+                children: vec![],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn printed_title() {
+        let section = Section::with_raw_title(1, "foo bar", "foo **bar**", vec![]);
+        assert_eq!(section.printed_title(), "foo **bar**");
+        let section = Section {
+            title: "baz".into(),
+            ..section
+        };
+        assert_eq!(section.printed_title(), "baz");
+    }
 }
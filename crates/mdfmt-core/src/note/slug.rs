@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use super::{block::Block, section::Section};
+
+const OBSIDIAN_RESERVED: &[char] = &['#', '|', '^', ':', '%', '[', ']'];
+
+/// How heading anchors are computed.
+#[derive(PartialEq, Debug, Default, Clone)]
+pub enum SlugStyle {
+    /// `Hello, World!` becomes `hello-world`, as on GitHub.
+    #[default]
+    Github,
+    /// `Hello, World!` stays `Hello, World!`, as in Obsidian's `[[#Heading]]` links.
+    Obsidian,
+    /// Lowercase words joined by `separator`, e.g. `hello_world`.
+    Custom { separator: String },
+}
+
+impl std::fmt::Display for SlugStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Github => write!(f, "github"),
+            Self::Obsidian => write!(f, "obsidian"),
+            Self::Custom { separator } => write!(f, "custom:{separator}"),
+        }
+    }
+}
+
+impl std::str::FromStr for SlugStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "github" => Ok(Self::Github),
+            "obsidian" => Ok(Self::Obsidian),
+            "custom" => Ok(Self::Custom {
+                separator: "-".to_string(),
+            }),
+            _ => match s.strip_prefix("custom:") {
+                Some(separator) => Ok(Self::Custom {
+                    separator: separator.to_string(),
+                }),
+                None => Err(anyhow!("unknown slug style `{s}`")),
+            },
+        }
+    }
+}
+
+impl SlugStyle {
+    pub fn slugify(&self, text: &str) -> String {
+        match self {
+            Self::Github => text
+                .trim()
+                .to_lowercase()
+                .chars()
+                .filter_map(|c| match c {
+                    ' ' => Some('-'),
+                    '-' | '_' => Some(c),
+                    c if c.is_alphanumeric() => Some(c),
+                    _ => None,
+                })
+                .collect(),
+            Self::Obsidian => text
+                .replace(OBSIDIAN_RESERVED, " ")
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" "),
+            Self::Custom { separator } => text
+                .to_lowercase()
+                .split(|c: char| !c.is_alphanumeric())
+                .filter(|v| !v.is_empty())
+                .collect::<Vec<&str>>()
+                .join(separator),
+        }
+    }
+}
+
+/// Hands out unique slugs by appending `-1`, `-2`, ... to repeated ones.
+#[derive(Default)]
+pub struct Slugger {
+    counts: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn slug(&mut self, base: &str) -> String {
+        let mut slug = base.to_string();
+        while let Some(count) = self.counts.get_mut(&slug) {
+            *count += 1;
+            slug = format!("{base}-{count}");
+        }
+        self.counts.insert(slug.clone(), 0);
+        slug
+    }
+}
+
+pub fn assign_slugs(blocks: &mut [Block], style: &SlugStyle) {
+    assign_slugs_inner(blocks, style, &mut Slugger::default());
+}
+
+fn assign_slugs_inner(blocks: &mut [Block], style: &SlugStyle, slugger: &mut Slugger) {
+    for block in blocks {
+        match block {
+            Block::AnonymousSection(children) => assign_slugs_inner(children, style, slugger),
+            Block::Section(section) => {
+                section.slug = slugger.slug(&style.slugify(&section.title));
+                assign_slugs_inner(&mut section.children, style, slugger);
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Returns slugs shared by more than one heading, with the indices of those headings in
+/// `sections(blocks)`.
+pub fn find_duplicate_slugs(blocks: &[Block], style: &SlugStyle) -> Vec<(String, Vec<usize>)> {
+    let mut indices: Vec<(String, Vec<usize>)> = vec![];
+    for (i, section) in sections(blocks).into_iter().enumerate() {
+        let slug = style.slugify(&section.title);
+        match indices.iter_mut().find(|(v, _)| *v == slug) {
+            Some((_, v)) => v.push(i),
+            None => indices.push((slug, vec![i])),
+        }
+    }
+    indices.retain(|(_, v)| v.len() > 1);
+    indices
+}
+
+/// Collects every section in document order.
+pub fn sections(blocks: &[Block]) -> Vec<&Section> {
+    let mut res: Vec<&Section> = vec![];
+    sections_inner(blocks, &mut res);
+    res
+}
+
+fn sections_inner<'a>(blocks: &'a [Block], res: &mut Vec<&'a Section>) {
+    for block in blocks {
+        match block {
+            Block::AnonymousSection(children) => sections_inner(children, res),
+            Block::Section(section) => {
+                res.push(section);
+                sections_inner(&section.children, res);
+            },
+            _ => {},
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn github_slug() {
+        assert_eq!(
+            SlugStyle::Github.slugify("Hello, World! `foo_bar` 日本語"),
+            "hello-world-foo_bar-日本語"
+        );
+    }
+
+    #[test]
+    fn obsidian_slug() {
+        assert_eq!(
+            SlugStyle::Obsidian.slugify("Hello: [World] #1"),
+            "Hello World 1"
+        );
+    }
+
+    #[test]
+    fn custom_slug() {
+        let style = SlugStyle::Custom {
+            separator: "_".into(),
+        };
+        assert_eq!(
+            style.slugify("Hello, World! foo-bar"),
+            "hello_world_foo_bar"
+        );
+    }
+
+    #[test]
+    fn deduplicate_slugs() {
+        let mut slugger = Slugger::default();
+        assert_eq!(slugger.slug("foo"), "foo");
+        assert_eq!(slugger.slug("foo"), "foo-1");
+        assert_eq!(slugger.slug("foo-1"), "foo-1-1");
+        assert_eq!(slugger.slug("foo"), "foo-2");
+    }
+
+    #[test]
+    fn duplicate_slugs() {
+        let blocks = vec![
+            Block::section(1, "Foo", vec![Block::section(2, "bar", vec![])]),
+            Block::section(1, "foo", vec![]),
+        ];
+        assert_eq!(
+            find_duplicate_slugs(&blocks, &SlugStyle::Github),
+            vec![("foo".to_string(), vec![0, 2])]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+use super::metadata::NoteStatus;
+
+/// The statuses a vault uses, their aliases and the transitions allowed between them.
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct StatusWorkflow {
+    /// Recognized statuses.
+    pub values: Vec<String>,
+    /// Alternative spellings, e.g. `wip = "in progress"`.
+    pub aliases: BTreeMap<String, String>,
+    /// Statuses reachable from each status. Any transition is allowed when empty.
+    pub transitions: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for StatusWorkflow {
+    fn default() -> Self {
+        Self {
+            values: [
+                NoteStatus::Todo,
+                NoteStatus::InProgress,
+                NoteStatus::Done,
+                NoteStatus::NotPlanned,
+                NoteStatus::Archived,
+            ]
+            .iter()
+            .map(|v| v.to_string())
+            .collect(),
+            aliases: BTreeMap::new(),
+            transitions: BTreeMap::new(),
+        }
+    }
+}
+
+impl StatusWorkflow {
+    /// Replaces an alias with the status it stands for.
+    pub fn resolve(&self, status: NoteStatus) -> NoteStatus {
+        match self.aliases.get(&status.to_string()) {
+            Some(v) => v.parse().unwrap_or(status),
+            None => status,
+        }
+    }
+
+    pub fn is_known(&self, status: &NoteStatus) -> bool {
+        let status = status.to_string();
+        self.values.contains(&status) || self.aliases.contains_key(&status)
+    }
+
+    /// Resolves `to` and checks that moving there from `from` is allowed.
+    pub fn transition(&self, from: Option<&NoteStatus>, to: &str) -> Result<NoteStatus> {
+        let to = self.resolve(to.parse()?);
+        if !self.is_known(&to) {
+            return Err(anyhow!("unknown status `{to}`"));
+        }
+
+        if let Some(from) = from {
+            let from = self.resolve(from.clone());
+            // Setting the status a note already has changes nothing
+            if from == to {
+                return Ok(to);
+            }
+            if let Some(allowed) = self.transitions.get(&from.to_string()) {
+                if !allowed.contains(&to.to_string()) {
+                    return Err(anyhow!(
+                        "cannot change status from `{from}` to `{to}`, expected one of: {}",
+                        allowed.join(", ")
+                    ));
+                }
+            }
+        }
+
+        Ok(to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn workflow() -> StatusWorkflow {
+        toml::from_str(
+            r#"
+            values = ["todo", "in progress", "review", "done"]
+
+            [aliases]
+            wip = "in progress"
+
+            [transitions]
+            todo = ["in progress"]
+            "in progress" = ["review"]
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolve_alias() {
+        assert_eq!(
+            workflow().resolve(NoteStatus::Other("wip".into())),
+            NoteStatus::InProgress
+        );
+        assert_eq!(workflow().resolve(NoteStatus::Done), NoteStatus::Done);
+    }
+
+    #[test]
+    fn transition() -> Result<()> {
+        let workflow = workflow();
+        assert_eq!(
+            workflow.transition(Some(&NoteStatus::Todo), "wip")?,
+            NoteStatus::InProgress
+        );
+        assert_eq!(
+            workflow.transition(Some(&NoteStatus::InProgress), "review")?,
+            NoteStatus::Other("review".into())
+        );
+        assert_eq!(workflow.transition(None, "done")?, NoteStatus::Done);
+        assert!(workflow
+            .transition(Some(&NoteStatus::Todo), "done")
+            .is_err());
+        assert!(workflow.transition(None, "blocked").is_err());
+        assert_eq!(
+            workflow.transition(Some(&NoteStatus::InProgress), "wip")?,
+            NoteStatus::InProgress
+        );
+        Ok(())
+    }
+}
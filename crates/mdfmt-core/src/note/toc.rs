@@ -1,9 +1,21 @@
 use anyhow::Result;
 use itertools::FoldWhile::{Continue, Done};
 use itertools::Itertools;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
+use super::{
+    block::Block,
+    options::{HeadingLevel, TocLinkStyle, TocOptions, TocStyle},
+    section::Section,
+    slug::SlugStyle,
+};
+
+/// A heading number written by `number_headings`, such as `1.2.`.
+static NUMBER_PREFIX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\d+(?:\.\d+)*)\.\s+").unwrap());
+
 #[derive(PartialEq, Serialize, Deserialize, Debug)]
 pub struct Toc(pub Vec<Node>);
 
@@ -13,12 +25,100 @@ pub struct Toc(pub Vec<Node>);
 pub struct Node {
     value: String,
     children: Vec<Node>,
+    style: ListStyle,
+}
+
+#[derive(PartialEq, Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ListStyle {
+    #[default]
+    Bullet,
+    Ordered,
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
-pub struct FlattenNode(pub usize, pub String);
+pub struct FlattenNode(pub usize, pub String, pub ListStyle);
 
 impl Toc {
+    /// Builds a TOC from section headings between `min_depth` and `max_depth`, at the levels
+    /// they're printed at with `heading_level`.
+    /// Sections shallower than `min_depth` are skipped and their children move up.
+    pub fn from_sections(
+        blocks: &[Block],
+        options: &TocOptions,
+        heading_level: HeadingLevel,
+    ) -> Self {
+        let levels = Levels {
+            depth: 1,
+            heading_level,
+        };
+        Self(Self::nodes_from_sections(
+            blocks,
+            options,
+            levels,
+            &[],
+            &mut 0,
+        ))
+    }
+
+    fn nodes_from_sections(
+        blocks: &[Block],
+        options: &TocOptions,
+        levels: Levels,
+        prefix: &[usize],
+        next: &mut usize,
+    ) -> Vec<Node> {
+        let mut res: Vec<Node> = vec![];
+        for block in blocks {
+            match block {
+                Block::AnonymousSection(children) => {
+                    res.extend(Self::nodes_from_sections(
+                        children,
+                        options,
+                        levels.next(),
+                        prefix,
+                        next,
+                    ));
+                },
+                Block::Section(section) if levels.of(section) <= options.max_depth => {
+                    if levels.of(section) < options.min_depth {
+                        res.extend(Self::nodes_from_sections(
+                            &section.children,
+                            options,
+                            levels.next(),
+                            prefix,
+                            next,
+                        ));
+                        continue;
+                    }
+
+                    *next += 1;
+                    let number = [prefix, &[*next]].concat();
+                    let children = Self::nodes_from_sections(
+                        &section.children,
+                        options,
+                        levels.next(),
+                        &number,
+                        &mut 0,
+                    );
+                    let value = entry(section, options.link);
+                    res.push(match options.style {
+                        TocStyle::Bullet => Node::new(&value, children),
+                        TocStyle::Ordered => Node::ordered(&value, children),
+                        TocStyle::Hierarchical if options.number_headings => {
+                            Node::new(&value, children)
+                        },
+                        TocStyle::Hierarchical => {
+                            Node::new(&format!("{} {value}", number_label(&number)), children)
+                        },
+                    });
+                },
+                _ => {},
+            }
+        }
+        res
+    }
+
     pub fn flatten_ref(&self) -> Vec<FlattenNode> {
         self.0.iter().flat_map(|v| v.flatten_ref()).collect()
     }
@@ -32,7 +132,7 @@ impl Node {
     }
 
     fn flatten_inner(self, indent: usize, values: &mut Vec<FlattenNode>) {
-        values.push(FlattenNode(indent, self.value));
+        values.push(FlattenNode(indent, self.value, self.style));
 
         for child in self.children.into_iter() {
             child.flatten_inner(indent + 1, values);
@@ -46,7 +146,7 @@ impl Node {
     }
 
     fn flatten_inner_ref(&self, indent: usize, values: &mut Vec<FlattenNode>) {
-        values.push(FlattenNode(indent, self.value.clone()));
+        values.push(FlattenNode(indent, self.value.clone(), self.style));
 
         for child in self.children.iter() {
             child.flatten_inner_ref(indent + 1, values);
@@ -54,11 +154,135 @@ impl Node {
     }
 }
 
+/// Prefixes headings between `min_depth` and `max_depth` with hierarchical numbers such as `1.2.`,
+/// replacing the numbers from a previous run. Other leading numbers, such as `2023 Goals`, are
+/// kept.
+pub fn number_headings(blocks: &mut [Block], options: &TocOptions, heading_level: HeadingLevel) {
+    let levels = Levels {
+        depth: 1,
+        heading_level,
+    };
+    number_headings_inner(blocks, options, levels, &[], Some(""), &mut 0);
+}
+
+/// Levels headings are printed at, for the depth filters.
+#[derive(Clone, Copy)]
+struct Levels {
+    /// Nesting depth of the sections being visited.
+    depth: u8,
+    heading_level: HeadingLevel,
+}
+
+impl Levels {
+    fn of(&self, section: &Section) -> u8 {
+        section.level(self.depth, self.heading_level)
+    }
+
+    fn next(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..*self
+        }
+    }
+}
+
+/// `old` is the number the parent heading had before, `""` at the top and `None` if it had
+/// none.
+fn number_headings_inner(
+    blocks: &mut [Block],
+    options: &TocOptions,
+    levels: Levels,
+    prefix: &[usize],
+    old: Option<&str>,
+    next: &mut usize,
+) {
+    for block in blocks {
+        match block {
+            Block::AnonymousSection(children) => {
+                number_headings_inner(children, options, levels.next(), prefix, old, next);
+            },
+            Block::Section(section) if levels.of(section) <= options.max_depth => {
+                if levels.of(section) < options.min_depth {
+                    number_headings_inner(
+                        &mut section.children,
+                        options,
+                        levels.next(),
+                        prefix,
+                        old,
+                        next,
+                    );
+                    continue;
+                }
+
+                *next += 1;
+                let number = [prefix, &[*next]].concat();
+                let label = number_label(&number);
+                let (title, own) = strip_number(&section.title, old);
+                let (title, own) = (format!("{label}. {title}"), own.map(str::to_string));
+                section.title = title;
+                let (raw_title, _) = strip_number(&section.raw_title, old);
+                section.raw_title = format!("{label}. {raw_title}");
+                number_headings_inner(
+                    &mut section.children,
+                    options,
+                    levels.next(),
+                    &number,
+                    own.as_deref(),
+                    &mut 0,
+                );
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Splits off a number written by `number_headings`: one more part than the parent's number
+/// `old`, starting with it. Returns the rest of `title` and the number.
+fn strip_number<'a>(title: &'a str, old: Option<&str>) -> (&'a str, Option<&'a str>) {
+    let Some((old, captures)) = old.zip(NUMBER_PREFIX.captures(title)) else {
+        return (title, None);
+    };
+    let number = captures.get(1).unwrap().as_str();
+    let own = match old {
+        "" => !number.contains('.'),
+        old => number
+            .strip_prefix(old)
+            .and_then(|v| v.strip_prefix('.'))
+            .is_some_and(|v| !v.contains('.')),
+    };
+    if own {
+        (&title[captures[0].len()..], Some(number))
+    } else {
+        (title, None)
+    }
+}
+
+fn number_label(number: &[usize]) -> String {
+    number.iter().map(|v| v.to_string()).join(".")
+}
+
+fn entry(section: &Section, link: TocLinkStyle) -> String {
+    match link {
+        TocLinkStyle::None => section.title.clone(),
+        TocLinkStyle::Obsidian => {
+            format!("[[#{}]]", SlugStyle::Obsidian.slugify(&section.title))
+        },
+        TocLinkStyle::Markdown => {
+            let text = section.title.replace('[', "\\[").replace(']', "\\]");
+            if section.slug.contains(char::is_whitespace) {
+                format!("[{text}](<#{}>)", section.slug)
+            } else {
+                format!("[{text}](#{})", section.slug)
+            }
+        },
+    }
+}
+
 #[derive(Debug)]
 enum Line {
     Unknown,
     Block(usize, String),
-    List(usize, String),
+    List(usize, String, ListStyle),
 }
 
 impl Toc {
@@ -98,7 +322,7 @@ impl Toc {
                     i = next_index;
                     res.push(Node::new(value, children));
                 },
-                Line::List(indent, value) => {
+                Line::List(indent, value, style) => {
                     let indent = base + *indent;
                     if indent <= parent {
                         break;
@@ -106,7 +330,7 @@ impl Toc {
 
                     let (next_index, children) = Self::parse_line(base, indent, lines, i + 1);
                     i = next_index;
-                    res.push(Node::new(value, children));
+                    res.push(Node::with_style(value, children, *style));
                 },
                 Line::Unknown => {
                     i += 1;
@@ -164,7 +388,11 @@ impl Line {
             })
             .into_inner();
         if end == '-' {
-            Some(Line::List(indent, s.chars().skip(skip).collect()))
+            Some(Line::List(
+                indent,
+                s.chars().skip(skip).collect(),
+                ListStyle::Bullet,
+            ))
         } else {
             None
         }
@@ -186,7 +414,11 @@ impl Line {
             })
             .into_inner();
         if end == '.' {
-            Some(Line::List(indent, s.chars().skip(skip).collect()))
+            Some(Line::List(
+                indent,
+                s.chars().skip(skip).collect(),
+                ListStyle::Ordered,
+            ))
         } else {
             None
         }
@@ -195,9 +427,18 @@ impl Line {
 
 impl Node {
     pub fn new(value: &str, children: Vec<Node>) -> Self {
+        Self::with_style(value, children, ListStyle::Bullet)
+    }
+
+    pub fn ordered(value: &str, children: Vec<Node>) -> Self {
+        Self::with_style(value, children, ListStyle::Ordered)
+    }
+
+    pub fn with_style(value: &str, children: Vec<Node>, style: ListStyle) -> Self {
         Self {
             value: value.to_string(),
             children,
+            style,
         }
     }
 }
@@ -208,6 +449,7 @@ mod tests {
     use pretty_assertions::assert_eq;
 
     use super::*;
+    use crate::note::sections;
 
     #[test]
     fn sharp_style_heading() -> Result<()> {
@@ -266,11 +508,11 @@ mod tests {
         assert_eq!(
             toc,
             Toc::new(vec![
-                Node::new(
+                Node::ordered(
                     "aaa",
-                    vec![Node::new("bbb", vec![Node::new("ccc", vec![])]),]
+                    vec![Node::ordered("bbb", vec![Node::ordered("ccc", vec![])]),]
                 ),
-                Node::new("ddd", vec![])
+                Node::ordered("ddd", vec![])
             ])
         );
         Ok(())
@@ -301,6 +543,161 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn from_sections() -> Result<()> {
+        let blocks = vec![
+            Block::anonymous_section(vec![Block::section(2, "aaa", vec![])]),
+            Block::section(
+                1,
+                "bbb",
+                vec![Block::section(
+                    2,
+                    "ccc",
+                    vec![Block::section(3, "ddd", vec![])],
+                )],
+            ),
+        ];
+        assert_eq!(
+            Toc::from_sections(&blocks, &TocOptions::default(), HeadingLevel::Keep),
+            Toc::new(vec![
+                Node::new("aaa", vec![]),
+                Node::new(
+                    "bbb",
+                    vec![Node::new("ccc", vec![Node::new("ddd", vec![])])]
+                ),
+            ])
+        );
+        assert_eq!(
+            Toc::from_sections(
+                &blocks,
+                &TocOptions {
+                    min_depth: 2,
+                    max_depth: 2,
+                    ..Default::default()
+                },
+                HeadingLevel::Keep
+            ),
+            Toc::new(vec![Node::new("aaa", vec![]), Node::new("ccc", vec![])])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_sections_with_skipped_level() -> Result<()> {
+        let blocks = vec![Block::section(
+            1,
+            "aaa",
+            vec![Block::section(3, "bbb", vec![])],
+        )];
+        let options = TocOptions {
+            max_depth: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            Toc::from_sections(&blocks, &options, HeadingLevel::Normalize),
+            Toc::new(vec![Node::new("aaa", vec![Node::new("bbb", vec![])])])
+        );
+        assert_eq!(
+            Toc::from_sections(&blocks, &options, HeadingLevel::Keep),
+            Toc::new(vec![Node::new("aaa", vec![])])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_sections_with_links() -> Result<()> {
+        let blocks = vec![Block::section(1, "Foo: [bar]", vec![])];
+        assert_eq!(
+            Toc::from_sections(
+                &blocks,
+                &TocOptions {
+                    link: TocLinkStyle::Obsidian,
+                    ..Default::default()
+                },
+                HeadingLevel::Keep
+            ),
+            Toc::new(vec![Node::new("[[#Foo bar]]", vec![])])
+        );
+        assert_eq!(
+            Toc::from_sections(
+                &blocks,
+                &TocOptions {
+                    link: TocLinkStyle::Markdown,
+                    ..Default::default()
+                },
+                HeadingLevel::Keep
+            ),
+            Toc::new(vec![Node::new("[Foo: \\[bar\\]](#foo-bar)", vec![])])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_sections_with_numbers() -> Result<()> {
+        let blocks = vec![Block::section(
+            1,
+            "title",
+            vec![
+                Block::section(2, "aaa", vec![Block::section(3, "bbb", vec![])]),
+                Block::section(2, "ccc", vec![]),
+            ],
+        )];
+        assert_eq!(
+            Toc::from_sections(
+                &blocks,
+                &TocOptions {
+                    min_depth: 2,
+                    style: TocStyle::Hierarchical,
+                    ..Default::default()
+                },
+                HeadingLevel::Keep
+            ),
+            Toc::new(vec![
+                Node::new("1 aaa", vec![Node::new("1.1 bbb", vec![])]),
+                Node::new("2 ccc", vec![]),
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn number_headings_twice() {
+        let mut blocks = vec![
+            Block::section(1, "aaa", vec![Block::section(2, "bbb", vec![])]),
+            Block::section(1, "ccc", vec![]),
+        ];
+        let options = TocOptions::default();
+        number_headings(&mut blocks, &options, HeadingLevel::Keep);
+        number_headings(&mut blocks, &options, HeadingLevel::Keep);
+        assert_eq!(
+            sections(&blocks)
+                .iter()
+                .map(|v| v.raw_title.clone())
+                .collect::<Vec<String>>(),
+            vec!["1. aaa", "1.1. bbb", "2. ccc"]
+        );
+    }
+
+    #[test]
+    fn number_headings_keeps_other_numbers() {
+        let mut blocks = vec![
+            Block::section(
+                1,
+                "2023 Goals",
+                vec![Block::section(2, "3. Things", vec![])],
+            ),
+            Block::section(1, "1. aaa", vec![Block::section(2, "1.1. bbb", vec![])]),
+        ];
+        number_headings(&mut blocks, &TocOptions::default(), HeadingLevel::Keep);
+        assert_eq!(
+            sections(&blocks)
+                .iter()
+                .map(|v| v.raw_title.clone())
+                .collect::<Vec<String>>(),
+            vec!["1. 2023 Goals", "1.1. 3. Things", "2. aaa", "2.1. bbb"]
+        );
+    }
+
     #[test]
     fn node_flatten() -> Result<()> {
         let node = Node::new(
@@ -313,10 +710,10 @@ mod tests {
         assert_eq!(
             node.flatten(),
             vec![
-                FlattenNode(1, String::from("aaa")),
-                FlattenNode(2, String::from("bbb")),
-                FlattenNode(3, String::from("ccc")),
-                FlattenNode(2, String::from("ddd")),
+                FlattenNode(1, String::from("aaa"), ListStyle::Bullet),
+                FlattenNode(2, String::from("bbb"), ListStyle::Bullet),
+                FlattenNode(3, String::from("ccc"), ListStyle::Bullet),
+                FlattenNode(2, String::from("ddd"), ListStyle::Bullet),
             ]
         );
         Ok(())
@@ -0,0 +1,163 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+
+pub static WIKI_LINK: Lazy<Regex> = Lazy::new(|| Regex::new(r"!?\[\[[^\[\]\n]+\]\]").unwrap());
+
+/// `[[target]]`, `[[target|alias]]`, `[[target#heading]]`, `[[target#^block]]` or an embed,
+/// `![[target]]`.
+#[derive(PartialEq, Debug, Default, Clone, Serialize, Deserialize, Tsify)]
+pub struct WikiLink {
+    /// Linked note. Empty for links within the same note, e.g. `[[#heading]]`.
+    pub target: String,
+    pub heading: Option<String>,
+    /// Block ID without `^`.
+    pub block: Option<String>,
+    pub alias: Option<String>,
+    pub embed: bool,
+}
+
+impl WikiLink {
+    /// Wiki links in `text`, in order.
+    pub fn find(text: &str) -> Vec<Self> {
+        WIKI_LINK
+            .find_iter(text)
+            .filter_map(|v| v.as_str().parse().ok())
+            .collect()
+    }
+}
+
+impl FromStr for WikiLink {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (embed, s) = match s.strip_prefix('!') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let inner = s
+            .strip_prefix("[[")
+            .and_then(|v| v.strip_suffix("]]"))
+            .filter(|v| !v.is_empty() && !v.contains(['[', ']', '\n']))
+            .ok_or_else(|| anyhow!("invalid wiki link `{s}`"))?;
+
+        let (link, alias) = match inner.split_once('|') {
+            Some((link, alias)) => (link, Some(alias.to_string())),
+            None => (inner, None),
+        };
+        // `[[target\|alias]]` in tables
+        let link = link.strip_suffix('\\').unwrap_or(link);
+        let (target, anchor) = match link.split_once('#') {
+            Some((target, anchor)) => (target, Some(anchor)),
+            None => (link, None),
+        };
+        let (heading, block) = match anchor {
+            Some(v) => match v.strip_prefix('^') {
+                Some(block) => (None, Some(block.to_string())),
+                None => (Some(v.to_string()), None),
+            },
+            None => (None, None),
+        };
+
+        Ok(Self {
+            target: target.to_string(),
+            heading,
+            block,
+            alias,
+            embed,
+        })
+    }
+}
+
+impl std::fmt::Display for WikiLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.embed {
+            write!(f, "!")?;
+        }
+        write!(f, "[[{}", self.target)?;
+        if let Some(heading) = &self.heading {
+            write!(f, "#{heading}")?;
+        }
+        if let Some(block) = &self.block {
+            write!(f, "#^{block}")?;
+        }
+        if let Some(alias) = &self.alias {
+            write!(f, "|{alias}")?;
+        }
+        write!(f, "]]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn parse() -> Result<()> {
+        assert_eq!(
+            "[[foo]]".parse::<WikiLink>()?,
+            WikiLink {
+                target: "foo".into(),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            "![[foo#bar|baz]]".parse::<WikiLink>()?,
+            WikiLink {
+                target: "foo".into(),
+                heading: Some("bar".into()),
+                alias: Some("baz".into()),
+                embed: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            "[[#^abc]]".parse::<WikiLink>()?,
+            WikiLink {
+                block: Some("abc".into()),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            "[[foo\\|bar]]".parse::<WikiLink>()?,
+            WikiLink {
+                target: "foo".into(),
+                alias: Some("bar".into()),
+                ..Default::default()
+            }
+        );
+        assert!("[[]]".parse::<WikiLink>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn print() -> Result<()> {
+        for s in [
+            "[[foo]]",
+            "[[foo|Foo]]",
+            "[[foo#Bar baz]]",
+            "![[foo#^abc|x]]",
+            "[[#Bar]]",
+        ] {
+            assert_eq!(s.parse::<WikiLink>()?.to_string(), s);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn find() {
+        assert_eq!(
+            WikiLink::find("[[a]] and ![[b|c]], not [a] or [[]]")
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>(),
+            vec!["[[a]]", "![[b|c]]"]
+        );
+    }
+}
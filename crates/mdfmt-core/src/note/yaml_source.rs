@@ -0,0 +1,161 @@
+use serde_yaml::{Mapping, Value};
+
+/// Front matter split into its top-level keys, keeping the text of each.
+#[derive(PartialEq, Debug)]
+pub struct YamlSource {
+    pub entries: Vec<Entry>,
+    /// Comments and blank lines after the last key.
+    pub trailer: String,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Entry {
+    pub key: Value,
+    pub value: Value,
+    /// Comments and blank lines before the key.
+    pub leading: String,
+    /// The key and its value as written.
+    pub text: String,
+}
+
+impl YamlSource {
+    /// Returns `None` for front matter that isn't a block mapping this can split.
+    pub fn parse(source: &str) -> Option<Self> {
+        let map: Mapping = serde_yaml::from_str(source).ok()?;
+
+        let mut entries: Vec<Entry> = vec![];
+        let mut entry_leading = String::new();
+        let mut leading = String::new();
+        let mut text = String::new();
+
+        for line in source.lines() {
+            if is_key_line(line) {
+                if !text.is_empty() {
+                    entries.push(Entry::parse(entry_leading, text)?);
+                    text = String::new();
+                }
+                entry_leading = std::mem::take(&mut leading);
+                text.push_str(line);
+                text.push('\n');
+            } else if text.is_empty() || is_comment_line(line) {
+                leading.push_str(line);
+                leading.push('\n');
+            } else {
+                // Comments and blank lines followed by more of the value belong to it
+                text.push_str(&std::mem::take(&mut leading));
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        if !text.is_empty() {
+            entries.push(Entry::parse(entry_leading, text)?);
+        }
+
+        if entries.len() != map.len() {
+            return None;
+        }
+        Some(Self {
+            entries,
+            trailer: leading,
+        })
+    }
+
+    pub fn get(&self, key: &Value) -> Option<&Entry> {
+        self.entries.iter().find(|v| &v.key == key)
+    }
+}
+
+impl Entry {
+    fn parse(leading: String, text: String) -> Option<Self> {
+        let map: Mapping = serde_yaml::from_str(&text).ok()?;
+        let (key, value) = map.into_iter().next()?;
+        Some(Self {
+            key,
+            value,
+            leading,
+            text,
+        })
+    }
+
+    /// `text` without the quotes around its string value, if it reads the same without them,
+    /// e.g. `date: '2023-08-01' # note` becomes `date: 2023-08-01 # note`.
+    pub fn unquoted(&self) -> Option<String> {
+        let value = self.value.as_str()?;
+        let text = ['\'', '"'].iter().find_map(|q| {
+            let quoted = format!("{q}{value}{q}");
+            self.text
+                .contains(&quoted)
+                .then(|| self.text.replacen(&quoted, value, 1))
+        })?;
+        let map: Mapping = serde_yaml::from_str(&text).ok()?;
+        (map.get(&self.key) == Some(&self.value)).then_some(text)
+    }
+}
+
+fn is_key_line(line: &str) -> bool {
+    !line.is_empty() && !line.starts_with([' ', '\t', '#', '-', '}', ']']) && !is_comment_line(line)
+}
+
+/// Indented lines are left out since they may be part of a block scalar.
+fn is_comment_line(line: &str) -> bool {
+    line.trim().is_empty() || line.starts_with('#')
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn unquoted() {
+        let source = YamlSource::parse("a: '2023-08-01' # x\nb: \"true\"\nc: 1\n").unwrap();
+        assert_eq!(
+            source.entries[0].unquoted(),
+            Some("a: 2023-08-01 # x\n".into())
+        );
+        assert_eq!(source.entries[1].unquoted(), None);
+        assert_eq!(source.entries[2].unquoted(), None);
+    }
+
+    #[test]
+    fn parse() {
+        let source = YamlSource::parse(indoc! {"
+            # heading
+            title: 'foo' # inline
+            tags:
+            - a
+
+            # about b
+            - b
+            # trailing
+        "})
+        .unwrap();
+        assert_eq!(
+            source,
+            YamlSource {
+                entries: vec![
+                    Entry {
+                        key: "title".into(),
+                        value: "foo".into(),
+                        leading: "# heading\n".into(),
+                        text: "title: 'foo' # inline\n".into(),
+                    },
+                    Entry {
+                        key: "tags".into(),
+                        value: Value::Sequence(vec!["a".into(), "b".into()]),
+                        leading: "".into(),
+                        text: "tags:\n- a\n\n# about b\n- b\n".into(),
+                    },
+                ],
+                trailer: "# trailing\n".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(YamlSource::parse("- a\n- b"), None);
+    }
+}
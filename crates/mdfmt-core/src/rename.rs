@@ -0,0 +1,195 @@
+use std::path::{Path, PathBuf};
+
+use markdown::mdast::Node;
+
+use crate::{
+    ast::wiki_link::wiki_link_of,
+    links::{local_path, normalize, relative_path, resolve, Resolver},
+};
+
+/// A note moved from `old` to `new`, and the notes of the vault for resolving links to it.
+pub struct Rename<'a> {
+    pub old: &'a Path,
+    pub new: &'a Path,
+    pub resolver: &'a Resolver,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Edit {
+    pub from: String,
+    pub to: String,
+}
+
+impl Rename<'_> {
+    /// Rewrites links in `node`, the content of `file`, that point to the moved note. Relative
+    /// links in the moved note itself are updated for its new directory.
+    pub fn rewrite(&self, file: &Path, node: &mut Node) -> Vec<Edit> {
+        let mut edits = vec![];
+        self.visit(file, node, &mut edits);
+        edits
+    }
+
+    fn visit(&self, file: &Path, node: &mut Node, edits: &mut Vec<Edit>) {
+        let link = wiki_link_of(node);
+        match node {
+            Node::Text(v) => {
+                if let Some(link) = link {
+                    self.rewrite_wiki_link(&mut v.value, &link.target, edits);
+                }
+            },
+            Node::Link(v) => self.rewrite_url(file, &mut v.url, edits),
+            Node::Definition(v) => self.rewrite_url(file, &mut v.url, edits),
+            Node::Image(v) => self.rewrite_url(file, &mut v.url, edits),
+            _ => {},
+        }
+        for child in node.children_mut().into_iter().flatten() {
+            self.visit(file, child, edits);
+        }
+    }
+
+    /// Rewrites `raw`, a wiki link split off by `wiki_link::split`, if `target` is the moved
+    /// note.
+    fn rewrite_wiki_link(&self, raw: &mut String, target: &str, edits: &mut Vec<Edit>) {
+        if target.is_empty() || self.resolver.wiki_link(target) != Some(self.old) {
+            return;
+        }
+
+        // Keep the heading, block and alias as written
+        let start = raw.find("[[").unwrap_or_default() + 2;
+        let rest = &raw[start + target.len()..];
+        let res = format!("{}{}{rest}", &raw[..start], self.wiki_target(target));
+        if res != *raw {
+            edits.push(Edit {
+                from: raw.clone(),
+                to: res.clone(),
+            });
+            *raw = res;
+        }
+    }
+
+    /// The file name when it's unambiguous, or the path, in the style of `target`.
+    fn wiki_target(&self, target: &str) -> String {
+        let mut path = normalize(self.new).with_extension("");
+        let name = PathBuf::from(path.file_name().unwrap_or_default());
+        if !target.contains('/') && !self.resolver.is_taken(&name.to_string_lossy(), self.old) {
+            path = name;
+        }
+
+        let mut res = path.to_string_lossy().replace('\\', "/");
+        if target.to_lowercase().ends_with(".md") {
+            res.push_str(".md");
+        }
+        res
+    }
+
+    fn rewrite_url(&self, file: &Path, url: &mut String, edits: &mut Vec<Edit>) {
+        let Some(path) = local_path(url) else {
+            return;
+        };
+        let moved = file == self.old;
+        let target = match self.resolver.path(file, &path) {
+            Some(target) if target == self.old => self.new.to_path_buf(),
+            // Links from the moved note to other files are relative to its old directory
+            _ if moved && !url.starts_with('/') => resolve(file, &path),
+            _ => return,
+        };
+
+        let path = if url.starts_with('/') {
+            format!(
+                "/{}",
+                normalize(&target).to_string_lossy().replace('\\', "/")
+            )
+        } else {
+            relative_path(if moved { self.new } else { file }, &target)
+        };
+        let suffix = &url[url.find(['#', '?']).unwrap_or(url.len())..];
+        let res = format!("{}{suffix}", path.replace(' ', "%20"));
+        if res != *url {
+            edits.push(Edit {
+                from: url.clone(),
+                to: res.clone(),
+            });
+            *url = res;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::to_mdast_from_str;
+
+    fn edits(file: &str, content: &str) -> Vec<(String, String)> {
+        let paths: Vec<PathBuf> = ["a.md", "notes/old.md", "notes/b.md", "other/new.md"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let resolver = Resolver::new(&paths);
+        let rename = Rename {
+            old: Path::new("notes/old.md"),
+            new: Path::new("archive/new.md"),
+            resolver: &resolver,
+        };
+
+        let mut node = to_mdast_from_str(content).unwrap();
+        rename
+            .rewrite(Path::new(file), &mut node)
+            .into_iter()
+            .map(|v| (v.from, v.to))
+            .collect()
+    }
+
+    fn edit(from: &str, to: &str) -> (String, String) {
+        (from.to_string(), to.to_string())
+    }
+
+    #[test]
+    fn wiki_links() {
+        assert_eq!(
+            edits(
+                "a.md",
+                "[[old]], [[Old#Heading|alias]], [[notes/old.md]] and [[b]]"
+            ),
+            vec![
+                // `[[new]]` would mean `other/new.md`
+                edit("[[old]]", "[[archive/new]]"),
+                edit("[[Old#Heading|alias]]", "[[archive/new#Heading|alias]]"),
+                edit("[[notes/old.md]]", "[[archive/new.md]]"),
+            ]
+        );
+        assert_eq!(
+            edits("a.md", "\\[[old]] and `[[old]]`"),
+            Vec::<(String, String)>::new()
+        );
+    }
+
+    #[test]
+    fn markdown_links() {
+        assert_eq!(
+            edits(
+                "notes/b.md",
+                "[x](old.md#top), [y](/notes/old.md) and [z](../a.md)"
+            ),
+            vec![
+                edit("old.md#top", "../archive/new.md#top"),
+                edit("/notes/old.md", "/archive/new.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn links_in_moved_note() {
+        assert_eq!(
+            edits(
+                "notes/old.md",
+                "[b](b.md), [a](../a.md), ![](img%20x.png) and [web](https://a.b)"
+            ),
+            vec![
+                edit("b.md", "../notes/b.md"),
+                edit("img%20x.png", "../notes/img%20x.png"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// A subset of JSON Schema for front matter: required keys, types and allowed values.
+/// The same structure can be written in TOML.
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    #[serde(default)]
+    pub required: Vec<String>,
+
+    #[serde(default)]
+    pub properties: BTreeMap<String, Property>,
+
+    /// Whether keys missing from `properties` are allowed.
+    #[serde(default = "default_true")]
+    pub additional_properties: bool,
+}
+
+#[derive(PartialEq, Debug, Default, Deserialize)]
+pub struct Property {
+    #[serde(rename = "type")]
+    pub kind: Option<OneOrManyTypes>,
+
+    #[serde(rename = "enum")]
+    pub values: Option<Vec<Value>>,
+
+    pub items: Option<Box<Property>>,
+}
+
+#[derive(PartialEq, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OneOrManyTypes {
+    One(Type),
+    Many(Vec<Type>),
+}
+
+#[derive(PartialEq, Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Type {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array,
+    Object,
+    Null,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Violation {
+    pub key: String,
+    pub message: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Self {
+            required: vec![],
+            properties: BTreeMap::new(),
+            additional_properties: true,
+        }
+    }
+}
+
+impl Schema {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("could not read schema `{}`", path.display()))?;
+        if path.extension().is_some_and(|v| v == "toml") {
+            toml::from_str(&content)
+                .with_context(|| format!("could not parse schema `{}`", path.display()))
+        } else {
+            serde_json::from_str(&content)
+                .with_context(|| format!("could not parse schema `{}`", path.display()))
+        }
+    }
+
+    /// Checks front matter, given as a JSON value. `None` means the note has no front matter.
+    pub fn validate(&self, value: Option<&Value>) -> Vec<Violation> {
+        let empty = serde_json::Map::new();
+        let map = match value {
+            Some(Value::Object(map)) => map,
+            Some(Value::Null) | None => &empty,
+            Some(_) => {
+                return vec![Violation::new("", "front matter must be a mapping")];
+            },
+        };
+
+        let mut res: Vec<Violation> = vec![];
+        for key in &self.required {
+            if !map.contains_key(key) {
+                res.push(Violation::new(key, "required key is missing"));
+            }
+        }
+        for (key, value) in map {
+            match self.properties.get(key) {
+                Some(property) => property.validate(key, value, &mut res),
+                None if !self.additional_properties => {
+                    res.push(Violation::new(key, "unknown key"));
+                },
+                None => {},
+            }
+        }
+        res
+    }
+}
+
+impl Property {
+    fn validate(&self, key: &str, value: &Value, res: &mut Vec<Violation>) {
+        if let Some(kind) = &self.kind {
+            let types = match kind {
+                OneOrManyTypes::One(v) => vec![*v],
+                OneOrManyTypes::Many(v) => v.clone(),
+            };
+            if !types.iter().any(|v| v.matches(value)) {
+                let expected = types
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" or ");
+                res.push(Violation::new(key, &format!("expected {expected}")));
+                return;
+            }
+        }
+        if let Some(values) = &self.values {
+            if !values.contains(value) {
+                let allowed = values
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                res.push(Violation::new(
+                    key,
+                    &format!("{value} is not one of {allowed}"),
+                ));
+            }
+        }
+        if let (Some(items), Value::Array(values)) = (&self.items, value) {
+            for (i, value) in values.iter().enumerate() {
+                items.validate(&format!("{key}[{i}]"), value, res);
+            }
+        }
+    }
+}
+
+impl Type {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+            Self::Null => value.is_null(),
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::String => write!(f, "string"),
+            Self::Number => write!(f, "number"),
+            Self::Integer => write!(f, "integer"),
+            Self::Boolean => write!(f, "boolean"),
+            Self::Array => write!(f, "array"),
+            Self::Object => write!(f, "object"),
+            Self::Null => write!(f, "null"),
+        }
+    }
+}
+
+impl Violation {
+    pub fn new(key: &str, message: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> Schema {
+        serde_json::from_value(json!({
+            "required": ["title"],
+            "properties": {
+                "title": { "type": "string" },
+                "status": { "type": "string", "enum": ["todo", "done"] },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            },
+            "additionalProperties": false
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn valid() {
+        let value = json!({ "title": "foo", "status": "done", "tags": ["bar"] });
+        assert_eq!(schema().validate(Some(&value)), vec![]);
+    }
+
+    #[test]
+    fn missing_front_matter() {
+        assert_eq!(
+            schema().validate(None),
+            vec![Violation::new("title", "required key is missing")]
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let value = json!({ "status": "blocked", "tags": ["bar", 1], "foo": 1 });
+        assert_eq!(
+            schema().validate(Some(&value)),
+            vec![
+                Violation::new("title", "required key is missing"),
+                Violation::new("foo", "unknown key"),
+                Violation::new("status", "\"blocked\" is not one of \"todo\", \"done\""),
+                Violation::new("tags[1]", "expected string"),
+            ]
+        );
+    }
+
+    #[test]
+    fn toml_schema() -> Result<()> {
+        let schema: Schema = toml::from_str(
+            r#"
+            required = ["title"]
+
+            [properties.title]
+            type = "string"
+
+            [properties.status]
+            type = "string"
+            enum = ["todo", "done"]
+
+            [properties.tags]
+            type = "array"
+            items = { type = "string" }
+            "#,
+        )?;
+        assert_eq!(
+            schema,
+            Schema {
+                additional_properties: true,
+                ..self::schema()
+            }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::{fs, path::Path, process::Command, time::SystemTime};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, FixedOffset, SubsecRound, Utc};
+
+use crate::note::CreatedSource;
+
+/// The current time in `offset`.
+pub fn now(offset: FixedOffset) -> DateTime<FixedOffset> {
+    Utc::now().trunc_subsecs(0).with_timezone(&offset)
+}
+
+/// When a note was created, for notes without `createdAt`. Falls back to the file's birth
+/// time, or its modification time if that's earlier or there is none, as copies and checkouts
+/// get a new birth time and many Linux file systems don't record one.
+pub fn created_at(
+    file: &Path,
+    source: CreatedSource,
+    offset: FixedOffset,
+) -> Result<DateTime<FixedOffset>> {
+    if source == CreatedSource::Git {
+        if let Some(date) = git_created_at(file) {
+            return Ok(date);
+        }
+    }
+
+    let metadata = fs::metadata(file)
+        .with_context(|| format!("could not read metadata of `{}`", file.display()))?;
+    let modified = metadata.modified()?;
+    let created = metadata.created().map_or(modified, |v| v.min(modified));
+    Ok(to_date_time(created, offset))
+}
+
+/// When a note was last modified, according to the file system.
+pub fn modified_at(file: &Path, offset: FixedOffset) -> Result<DateTime<FixedOffset>> {
+    let metadata = fs::metadata(file)
+        .with_context(|| format!("could not read metadata of `{}`", file.display()))?;
+    Ok(to_date_time(metadata.modified()?, offset))
+}
+
+fn to_date_time(time: SystemTime, offset: FixedOffset) -> DateTime<FixedOffset> {
+    DateTime::<Utc>::from(time)
+        .trunc_subsecs(0)
+        .with_timezone(&offset)
+}
+
+/// The author date of the commit that added `file`, if it's tracked by git.
+fn git_created_at(file: &Path) -> Option<DateTime<FixedOffset>> {
+    let dir = file.parent().filter(|v| !v.as_os_str().is_empty());
+    let output = Command::new("git")
+        .args([
+            "log",
+            "--diff-filter=A",
+            "--follow",
+            "--format=%aI",
+            "-1",
+            "--",
+        ])
+        .arg(file.file_name()?)
+        .current_dir(dir.unwrap_or(Path::new(".")))
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let s = String::from_utf8(output.stdout).ok()?;
+    DateTime::parse_from_rfc3339(s.trim()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Offset, Utc};
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) -> Result<()> {
+        let status = Command::new("git")
+            .args(["-c", "user.name=foo", "-c", "user.email=foo@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_DATE", "2023-08-01T09:00:00+09:00")
+            .status()?;
+        assert!(status.success());
+        Ok(())
+    }
+
+    #[test]
+    fn created_at_from_git() -> Result<()> {
+        let temp = tempdir()?;
+        let dir = temp.path();
+        let file = dir.join("note.md");
+        fs::write(&file, "foo\n")?;
+        git(dir, &["init", "-q"])?;
+        git(dir, &["add", "note.md"])?;
+        git(dir, &["commit", "-q", "-m", "add"])?;
+
+        assert_eq!(
+            created_at(&file, CreatedSource::Git, Utc.fix())?,
+            DateTime::parse_from_rfc3339("2023-08-01T09:00:00+09:00")?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn created_at_outside_git() -> Result<()> {
+        let temp = tempdir()?;
+        let dir = temp.path();
+        let file = dir.join("note.md");
+        fs::write(&file, "foo\n")?;
+
+        assert_eq!(git_created_at(&file), None);
+        assert_eq!(
+            created_at(&file, CreatedSource::Git, Utc.fix())?,
+            created_at(&file, CreatedSource::Ctime, Utc.fix())?
+        );
+        Ok(())
+    }
+}
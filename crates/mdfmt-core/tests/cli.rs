@@ -1,11 +1,21 @@
 use std::{
-    fs,
+    fs::{self, File},
     path::{Path, PathBuf},
+    process,
+    time::{Duration, UNIX_EPOCH},
 };
 
 use anyhow::{Context, Result};
-use mdfmt_core::{format, generate_index};
+use mdfmt_core::{
+    format_with, generate_index, run, validate, Config, FormatOptions, Schema, Violation,
+};
 use pretty_assertions::assert_eq;
+use tempfile::tempdir;
+
+fn run_args(args: &[&str]) -> Result<()> {
+    let args = ["md-fmt"].iter().chain(args).map(|v| v.to_string());
+    run(&Config::build(args)?)
+}
 
 #[test]
 fn markdown() -> Result<()> {
@@ -19,10 +29,20 @@ fn markdown() -> Result<()> {
             let dir = Path::new("fixtures/format").join(name);
             let input_path = dir.join("input.md");
             let output_path = dir.join("output.md");
+            let args_path = dir.join("args");
+
+            // Options of the fixture, written as command line arguments
+            let options = if args_path.exists() {
+                let args = fs::read_to_string(&args_path)?;
+                let args = ["md-fmt"].into_iter().chain(args.split_whitespace());
+                Config::build(args.map(String::from))?.format
+            } else {
+                FormatOptions::default()
+            };
 
             let input = fs::read_to_string(&input_path)
                 .with_context(|| format!("could not find input file `{}`", path.display()))?;
-            let actual = format(&input)?;
+            let actual = format_with(&input, &options)?;
 
             let expected = fs::read_to_string(&output_path).with_context(|| {
                 format!("could not find output file `{}`", output_path.display())
@@ -44,13 +64,26 @@ fn index() -> Result<()> {
     let entries = fs::read_dir("fixtures/index/input")?
         .map(|res| res.map(|e| e.path()))
         .collect::<std::result::Result<Vec<_>, std::io::Error>>()?;
-    let paths = entries
-        .into_iter()
-        .filter(|v| v.is_file())
-        .collect::<Vec<PathBuf>>();
     let output_path = Path::new("fixtures/index/output.json");
 
-    let actual = generate_index(&paths)?;
+    // Notes without front matter get their dates from the file, so the notes are copied to
+    // set them
+    let temp = tempdir()?;
+    let mut paths = vec![];
+    for entry in entries.into_iter().filter(|v| v.is_file()) {
+        let path = temp.path().join(entry.file_name().unwrap());
+        fs::copy(&entry, &path)?;
+        paths.push(path);
+    }
+    File::options()
+        .write(true)
+        .open(temp.path().join("example_without_metadata.md"))?
+        .set_modified(UNIX_EPOCH + Duration::from_secs(1690848000))?;
+
+    let actual = generate_index(&paths)?.replace(
+        &format!("{}/", temp.path().display()),
+        "fixtures/index/input/",
+    );
 
     let expected = fs::read_to_string(output_path)
         .with_context(|| format!("could not find output file `{}`", output_path.display()))?;
@@ -63,3 +96,289 @@ fn index() -> Result<()> {
     );
     Ok(())
 }
+
+#[test]
+fn index_without_metadata() -> Result<()> {
+    let paths = vec![PathBuf::from(
+        "fixtures/index/input/example_without_metadata.md",
+    )];
+
+    let actual = generate_index(&paths)?;
+    let actual: serde_json::Value = serde_json::from_str(&actual)?;
+
+    let meta = &actual[0]["meta"];
+    assert_eq!(meta["title"], "example_without_metadata");
+    assert!(meta["createdAt"].is_string());
+    assert!(meta["updatedAt"].is_string());
+    Ok(())
+}
+
+#[test]
+fn index_metadata_only() -> Result<()> {
+    let temp = tempdir()?;
+    let dir = temp.path();
+    let (a, b) = (dir.join("a.md"), dir.join("journal/2023-08-01.md"));
+    let index = dir.join("index.json");
+    fs::create_dir_all(dir.join("journal"))?;
+    fs::write(&a, "---\ntitle: foo\n---\n")?;
+    fs::write(&b, "# bar\n")?;
+
+    run_args(&[
+        "--index",
+        index.to_str().unwrap(),
+        "--index-metadata-only",
+        "--journal-pattern",
+        "journal/{year}-{month}-{day}.md",
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+    ])?;
+    let content = fs::read_to_string(&index)?;
+    assert!(content.contains(r#""title":"foo""#), "{content}");
+    assert!(!content.contains("2023-08-01"), "{content}");
+    Ok(())
+}
+
+#[test]
+fn validate_front_matter() -> Result<()> {
+    let schema: Schema = serde_json::from_str(
+        r#"{ "required": ["title"], "properties": { "title": { "type": "string" } } }"#,
+    )?;
+    let missing = vec![Violation::new("title", "required key is missing")];
+    let invalid = vec![Violation::new("title", "expected string")];
+
+    for input in [
+        "---\ntitle: foo\n---\n",
+        "+++\ntitle = \"foo\"\n+++\n",
+        ";;;\n{ \"title\": \"foo\" }\n;;;\n",
+        "{ \"title\": \"foo\" }\n",
+    ] {
+        assert_eq!(validate(&schema, input)?, vec![], "{input}");
+    }
+    for input in [
+        "---\ntitle: 1\n---\n",
+        "+++\ntitle = 1\n+++\n",
+        "{ \"title\": 1 }\n",
+    ] {
+        assert_eq!(validate(&schema, input)?, invalid, "{input}");
+    }
+    assert_eq!(validate(&schema, "+++\ntags = []\n+++\n")?, missing);
+    assert_eq!(validate(&schema, "# foo\n")?, missing);
+    Ok(())
+}
+
+#[test]
+fn status() -> Result<()> {
+    let temp = tempdir()?;
+    let dir = temp.path();
+    let file = dir.join("note.md");
+    let path = file.to_str().unwrap();
+    fs::write(
+        &file,
+        "---\nstatus: todo\nupdatedAt: 2023-01-01T00:00:00Z\n---\n",
+    )?;
+
+    run_args(&["status", path, "todo"])?;
+    let content = fs::read_to_string(&file)?;
+    assert!(content.contains("2023-01-01T00:00:00Z"), "{content}");
+
+    run_args(&["status", path, "done"])?;
+    let content = fs::read_to_string(&file)?;
+    assert!(content.contains("status: done"), "{content}");
+    assert!(!content.contains("2023-01-01"), "{content}");
+    Ok(())
+}
+
+#[test]
+fn timestamps() -> Result<()> {
+    let temp = tempdir()?;
+    let dir = temp.path();
+    let file = dir.join("note.md");
+    let path = file.to_str().unwrap();
+    fs::write(
+        &file,
+        "---\ncreatedAt: 2023-01-01T00:00:00Z\n---\n# foo\n\nbar\n",
+    )?;
+
+    // Formatting alone leaves `updatedAt` out
+    run_args(&["--write", "--timestamps", path])?;
+    let content = fs::read_to_string(&file)?;
+    assert!(content.ends_with("---\n# foo\nbar\n"), "{content}");
+    assert!(!content.contains("updatedAt:"), "{content}");
+
+    run_args(&["--write", "--timestamps", "--number-headings", path])?;
+    let content = fs::read_to_string(&file)?;
+    assert!(content.contains("updatedAt:"), "{content}");
+    assert!(content.contains("# 1. foo"), "{content}");
+    Ok(())
+}
+
+#[test]
+fn index_cache() -> Result<()> {
+    let temp = tempdir()?;
+    let dir = temp.path();
+    let (a, b) = (dir.join("a.md"), dir.join("b.md"));
+    let index = dir.join("index.json");
+    let args = [
+        "--index",
+        index.to_str().unwrap(),
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+    ];
+    let set_modified = |file: &Path, secs| -> Result<()> {
+        File::options()
+            .write(true)
+            .open(file)?
+            .set_modified(UNIX_EPOCH + Duration::from_secs(secs))?;
+        Ok(())
+    };
+    fs::write(&a, "---\ntitle: foo\n---\n")?;
+    fs::write(&b, "# bar\n")?;
+    set_modified(&b, 1690848000)?;
+
+    run_args(&args)?;
+    let content = fs::read_to_string(&index)?;
+    assert!(content.contains(r#""title":"foo""#), "{content}");
+    assert!(
+        content.contains(r#""updatedAt":"2023-08-01T00:00:00Z""#),
+        "{content}"
+    );
+
+    // A changed note is parsed again, and a touched one gets its new modification time
+    fs::write(&a, "---\ntitle: baz\n---\n")?;
+    set_modified(&b, 1690934400)?;
+    run_args(&args)?;
+    let content = fs::read_to_string(&index)?;
+    assert!(content.contains(r#""title":"baz""#), "{content}");
+    assert!(
+        content.contains(r#""updatedAt":"2023-08-02T00:00:00Z""#),
+        "{content}"
+    );
+    Ok(())
+}
+
+#[test]
+fn graph() -> Result<()> {
+    let temp = tempdir()?;
+    let dir = temp.path();
+    let (a, b, c) = (
+        dir.join("a.md"),
+        dir.join("b.md"),
+        dir.join("v1.2 notes.md"),
+    );
+    let output = dir.join("graph.json");
+    fs::write(
+        &a,
+        "[[b]], [[v1.2 notes]], ![[photo.png]] and [[missing]]\n",
+    )?;
+    fs::write(&b, "[back](a.md)\n")?;
+    fs::write(&c, "# Notes\n")?;
+
+    run_args(&[
+        "--graph",
+        output.to_str().unwrap(),
+        a.to_str().unwrap(),
+        b.to_str().unwrap(),
+        c.to_str().unwrap(),
+    ])?;
+    let graph: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output)?)?;
+
+    let id = |path: &Path| path.display().to_string();
+    let edges = graph["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| (v["source"].as_str().unwrap(), v["target"].as_str().unwrap()))
+        .map(|(source, target)| (source.to_string(), target.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        edges,
+        vec![(id(&a), id(&b)), (id(&a), id(&c)), (id(&b), id(&a))]
+    );
+    assert_eq!(graph["unresolved"][0]["link"], "missing");
+    assert_eq!(graph["unresolved"].as_array().map(Vec::len), Some(1));
+    assert_eq!(graph["backlinks"][id(&c)][0], id(&a).as_str());
+    Ok(())
+}
+
+#[test]
+fn move_note() -> Result<()> {
+    let temp = tempdir()?;
+    let dir = temp.path();
+    let (old, new, other) = (
+        dir.join("notes/a.md"),
+        dir.join("archive/z.md"),
+        dir.join("b.md"),
+    );
+    fs::create_dir_all(dir.join("notes"))?;
+    fs::write(&old, "# A\nSee [[a]].\n")?;
+    fs::write(&other, "[[a]] and [a](notes/a.md)\n")?;
+    let glob = format!("{}/**/*.md", dir.display());
+    let args = [
+        "--glob",
+        &glob,
+        "mv",
+        old.to_str().unwrap(),
+        new.to_str().unwrap(),
+    ];
+
+    run_args(&[&args[..], &["--dry-run"]].concat())?;
+    assert!(old.is_file() && !new.exists());
+    assert_eq!(fs::read_to_string(&other)?, "[[a]] and [a](notes/a.md)\n");
+
+    // Nothing changes when the destination is taken
+    fs::create_dir_all(dir.join("archive"))?;
+    fs::write(&new, "# Z\n")?;
+    assert!(run_args(&args).is_err());
+    assert!(old.is_file());
+    assert_eq!(fs::read_to_string(&new)?, "# Z\n");
+    assert_eq!(fs::read_to_string(&other)?, "[[a]] and [a](notes/a.md)\n");
+    fs::remove_file(&new)?;
+
+    run_args(&args)?;
+    assert_eq!(fs::read_to_string(&new)?, "# A\nSee [[z]].\n");
+    assert!(!dir.join("notes").exists());
+    assert_eq!(fs::read_to_string(&other)?, "[[z]] and [a](archive/z.md)\n");
+    Ok(())
+}
+
+#[test]
+fn lint() -> Result<()> {
+    let temp = tempdir()?;
+    let dir = temp.path();
+    let file = dir.join("note.md");
+    let config = dir.join("config.toml");
+    let path = file.to_str().unwrap();
+    fs::write(&file, "# Foo\n\ntext\n\n## Foo\n\nhttps://a.com\n")?;
+
+    // Rules are warnings unless configured as errors
+    run_args(&["--lint", path])?;
+
+    fs::write(&config, "[lint.rules]\nduplicate-heading = \"error\"\n")?;
+    let config = config.to_str().unwrap();
+    let err = run_args(&["--lint", "--config", config, path]).unwrap_err();
+    assert_eq!(err.to_string(), "found 1 lint error(s)");
+
+    let output = process::Command::new(env!("CARGO_BIN_EXE_md-fmt"))
+        .args(["--lint", "--lint-format", "json", "--config", config, path])
+        .output()?;
+    assert!(!output.status.success());
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    let diagnostics = diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| {
+            format!(
+                "{}: {}: {}",
+                v["line"],
+                v["rule"].as_str().unwrap(),
+                v["severity"].as_str().unwrap()
+            )
+        })
+        .collect::<Vec<String>>();
+    assert_eq!(
+        diagnostics,
+        vec!["5: duplicate-heading: error", "7: bare-url: warning"]
+    );
+    Ok(())
+}